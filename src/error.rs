@@ -1,5 +1,6 @@
 use ark_std::fmt;
 
+use ark_std::boxed::Box;
 use ark_std::string::String;
 use core::fmt::Formatter;
 /// Error type for this crate
@@ -13,10 +14,44 @@ pub enum Error {
     SerializationError,
     /// Random Generator Error
     RNGError,
+    /// Attempt to prove a polynomial without any product
+    EmptyPolynomial,
+    /// Attempt to prove a polynomial without any variable
+    ConstantPolynomial,
+    /// Attempt to run a round after the last round of the protocol
+    ProtocolFinished,
+    /// The proof does not have one round per variable
+    WrongProofLength {
+        /// number of rounds of the protocol
+        expected: usize,
+        /// number of rounds of the proof
+        got: usize,
+    },
+    /// The round polynomial `g` of a round does not satisfy `g(0) + g(1) = expected`
+    RoundCheckFailed(Box<RoundCheckFailure>),
+    /// The degree of the round polynomials, plus one for the length of the round messages, does not
+    /// fit in a `usize`
+    DegreeOverflow,
     /// Other caused by other operations
     OtherError(String),
 }
 
+/// Details of `Error::RoundCheckFailed`, boxed to keep `Error` small.
+///
+/// The field elements are formatted with `Display`.
+#[derive(fmt::Debug)]
+pub struct RoundCheckFailure {
+    /// index of the round, starting from 0
+    pub round: usize,
+    /// the sum expected from this round, i.e. the claimed sum or the previous round polynomial
+    /// evaluated at its challenge
+    pub expected: String,
+    /// `g(0)`
+    pub g0: String,
+    /// `g(1)`
+    pub g1: String,
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if let Self::OtherError(s) = self {
@@ -79,6 +79,46 @@ pub fn start_phase2_sumcheck<F: Field>(
     IPForMLSumcheck::prover_init(&poly)
 }
 
+/// Returns the polynomial in `x, y` whose sum over the hypercube is the GKR layer claim at `r`:
+///
+/// $$\tilde{W}_{out}(r) = \sum_{x,y} \tilde{add}(r,x,y)(W(x)+W(y)) + \tilde{mult}(r,x,y)W(x)W(y)$$
+///
+/// * `add_mle`, `mult_mle`: wiring predicates of the layer, over the variables of the output gate
+///   `z`, then of the left input `x`, then of the right input `y`
+/// * `w_mle`: values of the input layer, over the variables of a gate
+/// * `r`: the point of the claim, i.e. the value of `z`
+///
+/// The returned polynomial has the variables of `x` first, then those of `y`, so that the first
+/// rounds of its sumcheck fix `x` and its subclaim point is `x || y`.
+pub fn layer_polynomial<F: Field>(
+    add_mle: &SparseMultilinearExtension<F>,
+    mult_mle: &SparseMultilinearExtension<F>,
+    w_mle: &DenseMultilinearExtension<F>,
+    r: &[F],
+) -> ListOfProductsOfPolynomials<F> {
+    let dim = w_mle.num_vars;
+    assert_eq!(add_mle.num_vars, r.len() + 2 * dim);
+    assert_eq!(mult_mle.num_vars, r.len() + 2 * dim);
+
+    let add_r = Rc::new(add_mle.fix_variables(r).to_dense_multilinear_extension());
+    let mult_r = Rc::new(mult_mle.fix_variables(r).to_dense_multilinear_extension());
+    let mask = (1 << dim) - 1;
+    let w_x = Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+        2 * dim,
+        (0..1 << (2 * dim)).map(|xy| w_mle[xy & mask]).collect(),
+    ));
+    let w_y = Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+        2 * dim,
+        (0..1 << (2 * dim)).map(|xy| w_mle[xy >> dim]).collect(),
+    ));
+
+    let mut poly = ListOfProductsOfPolynomials::new(2 * dim);
+    poly.add_product(vec![add_r.clone(), w_x.clone()], F::one());
+    poly.add_product(vec![add_r, w_y.clone()], F::one());
+    poly.add_product(vec![mult_r, w_x, w_y], F::one());
+    poly
+}
+
 /// Sumcheck Argument for GKR Round Function
 pub struct GKRRoundSumcheck<F: Field> {
     _marker: PhantomData<F>,
@@ -1,6 +1,7 @@
-use crate::gkr_round_sumcheck::GKRRoundSumcheck;
-use crate::rng::{Blake2s512Rng, FeedableRNG};
-use ark_ff::Field;
+use crate::gkr_round_sumcheck::{layer_polynomial, GKRRoundSumcheck};
+use crate::ml_sumcheck::MLSumcheck;
+use crate::rng::{Blake2s512Rng, SetupRNG};
+use ark_ff::{Field, One};
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension};
 use ark_std::rand::RngCore;
 use ark_std::{test_rng, UniformRand};
@@ -86,3 +87,41 @@ fn test_extract() {
 
     assert_eq!(actual_sum, expected_sum);
 }
+
+#[test]
+fn test_layer_polynomial() {
+    let mut rng = test_rng();
+    // two gates over four inputs: gate 0 adds inputs 0 and 1, gate 1 multiplies inputs 2 and 3
+    let w: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let w_mle = DenseMultilinearExtension::from_evaluations_slice(2, &w);
+    let gate = |z: usize, x: usize, y: usize| z | (x << 1) | (y << 3);
+    let add_mle = SparseMultilinearExtension::from_evaluations(5, &[(gate(0, 0, 1), Fr::one())]);
+    let mult_mle = SparseMultilinearExtension::from_evaluations(5, &[(gate(1, 2, 3), Fr::one())]);
+    let output = DenseMultilinearExtension::from_evaluations_vec(1, vec![w[0] + w[1], w[2] * w[3]]);
+
+    let r = vec![Fr::rand(&mut rng)];
+    let claim = output.evaluate(&r).unwrap();
+    let poly = layer_polynomial(&add_mle, &mult_mle, &w_mle, &r);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::extract_sum(&proof), claim);
+    let subclaim = MLSumcheck::verify(&poly.info(), claim, &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let (x, y) = subclaim.point.split_at(2);
+    let mut rxy = r.clone();
+    rxy.extend_from_slice(x);
+    rxy.extend_from_slice(y);
+    let (w_x, w_y) = (w_mle.evaluate(x).unwrap(), w_mle.evaluate(y).unwrap());
+    assert_eq!(
+        add_mle.evaluate(&rxy).unwrap() * (w_x + w_y)
+            + mult_mle.evaluate(&rxy).unwrap() * w_x * w_y,
+        subclaim.expected_evaluation
+    );
+
+    let wrong = SparseMultilinearExtension::from_evaluations(5, &[(gate(1, 2, 2), Fr::one())]);
+    let poly = layer_polynomial(&add_mle, &wrong, &w_mle, &r);
+    assert_ne!(
+        MLSumcheck::extract_sum(&MLSumcheck::prove(&poly).unwrap()),
+        claim
+    );
+}
@@ -10,7 +10,7 @@
 #![deny(renamed_and_removed_lints, stable_features, unused_allocation)]
 #![deny(unused_comparisons, bare_trait_objects, unused_must_use)]
 
-pub use error::Error;
+pub use error::{Error, RoundCheckFailure};
 
 /// use ark_std for std
 #[macro_use]
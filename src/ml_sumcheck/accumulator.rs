@@ -0,0 +1,82 @@
+//! Verifier that defers the final evaluation checks of many proofs to a single batched check.
+
+use crate::ml_sumcheck::data_structures::PolynomialInfo;
+use crate::ml_sumcheck::protocol::verifier::SubClaim;
+use crate::ml_sumcheck::{MLSumcheck, Proof};
+use crate::rng::{Blake2s512Rng, FeedableRNG, SetupRNG};
+use ark_ff::Field;
+use ark_std::vec::Vec;
+
+/// Accumulates the subclaims of proofs generated by `MLSumcheck::prove`, and checks all of them at
+/// once in `finalize`.
+///
+/// The round checks of each proof are done as soon as it is added. Only the evaluation of each
+/// polynomial at its subclaim point is deferred, so that the openings can be batched.
+pub struct VerificationAccumulator<F: Field> {
+    subclaims: Vec<SubClaim<F>>,
+}
+
+impl<F: Field> Default for VerificationAccumulator<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> VerificationAccumulator<F> {
+    /// Returns an accumulator without any proof.
+    pub fn new() -> Self {
+        Self {
+            subclaims: Vec::new(),
+        }
+    }
+
+    /// number of accumulated proofs
+    pub fn num_proofs(&self) -> usize {
+        self.subclaims.len()
+    }
+
+    /// verify the rounds of `proof` for `claimed_sum`, and store its subclaim
+    ///
+    /// Returns the error of `MLSumcheck::verify` if the rounds do not check, in which case nothing is
+    /// stored.
+    pub fn add(
+        &mut self,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(), crate::Error> {
+        let subclaim = MLSumcheck::verify(polynomial_info, claimed_sum, proof)?;
+        self.subclaims.push(subclaim);
+        Ok(())
+    }
+
+    /// check all stored subclaims with a single call to `oracle`
+    ///
+    /// `oracle` receives the subclaim points in the order the proofs were added, along with one
+    /// coefficient per point, and returns the random linear combination
+    /// `sum_i coefficients[i] * P_i(points[i])`, where `P_i` is the polynomial of the `i`-th proof.
+    /// The coefficients are derived from all subclaims with a `Blake2s512Rng` transcript. Returns
+    /// whether the combination matches the expected evaluations, or the error of `oracle`.
+    pub fn finalize(
+        self,
+        oracle: impl FnOnce(&[Vec<F>], &[F]) -> Result<F, crate::Error>,
+    ) -> Result<bool, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        for subclaim in &self.subclaims {
+            fs_rng.feed_batch(&[&subclaim.point, &subclaim.expected_evaluation])?;
+        }
+        let coefficients: Vec<F> = self
+            .subclaims
+            .iter()
+            .map(|_| F::rand(&mut fs_rng))
+            .collect();
+        let expected: F = self
+            .subclaims
+            .iter()
+            .zip(&coefficients)
+            .map(|(subclaim, c)| subclaim.expected_evaluation * c)
+            .sum();
+        let points: Vec<Vec<F>> = self.subclaims.into_iter().map(|s| s.point).collect();
+        Ok(oracle(&points, &coefficients)? == expected)
+    }
+}
@@ -0,0 +1,23 @@
+//! Hook for committing to a polynomial before proving its sum.
+
+use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use ark_ff::Field;
+use ark_serialize::CanonicalSerialize;
+
+/// A commitment scheme for the polynomials proved by `MLSumcheck`.
+///
+/// The scheme is left to the user. Its commitments are fed to the transcript by
+/// `MLSumcheck::prove_committed` and `MLSumcheck::verify_committed`, which binds the proof to the
+/// committed polynomial.
+pub trait CommitmentScheme<F: Field> {
+    /// public parameters of the scheme
+    type Params;
+    /// commitment to a polynomial
+    type Commitment: CanonicalSerialize;
+
+    /// commit to `polynomial`
+    fn commit(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        params: &Self::Params,
+    ) -> Self::Commitment;
+}
@@ -1,10 +1,16 @@
 //! Defines the data structures used by the `MLSumcheck` protocol.
 
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 use ark_std::cmp::max;
+use ark_std::rand::RngCore;
 use ark_std::rc::Rc;
+#[cfg(feature = "viz")]
+use ark_std::string::String;
 use ark_std::vec::Vec;
 use hashbrown::HashMap;
 /// Stores a list of products of `DenseMultilinearExtension` that is meant to be added together.
@@ -21,20 +27,35 @@ use hashbrown::HashMap;
 /// $$\sum_{i=0}^{n}C_i\cdot\prod_{j=0}^{m_i}P_{ij}$$
 ///
 /// The result polynomial is used as the prover key.
+///
+/// The coefficients have type `C`, which is `F` unless the list is created by
+/// `new_with_coefficients`. Coefficients of another type, such as small integers, are only converted
+/// into `F` by `IPForMLSumcheck::prover_init`.
 #[derive(Clone)]
-pub struct ListOfProductsOfPolynomials<F: Field> {
+pub struct ListOfProductsOfPolynomials<F: Field, C = F> {
     /// max number of multiplicands in each product
     pub max_multiplicands: usize,
     /// number of variables of the polynomial
     pub num_variables: usize,
     /// list of reference to products (as usize) of multilinear extension
-    pub products: Vec<(F, Vec<usize>)>,
+    pub products: Vec<(C, Vec<usize>)>,
     /// Stores multilinear extensions in which product multiplicand can refer to.
     pub flattened_ml_extensions: Vec<Rc<DenseMultilinearExtension<F>>>,
     raw_pointers_lookup_table: HashMap<*const DenseMultilinearExtension<F>, usize>,
 }
 
-impl<F: Field> ListOfProductsOfPolynomials<F> {
+impl<F: Field, C> ListOfProductsOfPolynomials<F, C> {
+    /// Returns an empty polynomial whose coefficients have type `C`
+    pub fn new_with_coefficients(num_variables: usize) -> Self {
+        ListOfProductsOfPolynomials {
+            max_multiplicands: 0,
+            num_variables,
+            products: Vec::new(),
+            flattened_ml_extensions: Vec::new(),
+            raw_pointers_lookup_table: HashMap::new(),
+        }
+    }
+
     /// Extract the max number of multiplicands and number of variables of the list of products.
     pub fn info(&self) -> PolynomialInfo {
         PolynomialInfo {
@@ -42,46 +63,69 @@ impl<F: Field> ListOfProductsOfPolynomials<F> {
             num_variables: self.num_variables,
         }
     }
-}
 
-#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-/// Stores the number of variables and max number of multiplicands of the added polynomial used by the prover.
-/// This data structures will is used as the verifier key.
-pub struct PolynomialInfo {
-    /// max number of multiplicands in each product
-    pub max_multiplicands: usize,
-    /// number of variables of the polynomial
-    pub num_variables: usize,
-}
+    /// Returns the number of products in the list.
+    pub fn num_products(&self) -> usize {
+        self.products.len()
+    }
 
-impl<F: Field> ListOfProductsOfPolynomials<F> {
-    /// Returns an empty polynomial
-    pub fn new(num_variables: usize) -> Self {
-        ListOfProductsOfPolynomials {
-            max_multiplicands: 0,
-            num_variables,
-            products: Vec::new(),
-            flattened_ml_extensions: Vec::new(),
-            raw_pointers_lookup_table: HashMap::new(),
-        }
+    /// Returns `(num_variables, max_multiplicands, num_products)` of the list of products.
+    pub fn shape(&self) -> (usize, usize, usize) {
+        (
+            self.num_variables,
+            self.max_multiplicands,
+            self.num_products(),
+        )
     }
 
     /// Add a list of multilinear extensions that is meant to be multiplied together.
     /// The resulting polynomial will be multiplied by the scalar `coefficient`.
+    ///
+    /// An empty list is the constant polynomial `coefficient`, which adds
+    /// `coefficient * 2^num_variables` to the sum. It counts as one multiplicand in
+    /// `max_multiplicands`, so that its round polynomials have degree 1.
+    ///
+    /// Panics with the error of `try_add_product` if the product is malformed.
     pub fn add_product(
         &mut self,
         product: impl IntoIterator<Item = Rc<DenseMultilinearExtension<F>>>,
-        coefficient: F,
+        coefficient: C,
     ) {
+        if let Err(e) = self.try_add_product(product, coefficient) {
+            panic!("{}", e);
+        }
+    }
+
+    /// same as `add_product`, but returns an error instead of panicking
+    ///
+    /// Returns `Error::OtherError`, and leaves the polynomial unchanged, if a multiplicand does not
+    /// have `num_variables` variables or does not have exactly `2^num_vars` evaluations, which
+    /// catches tables that are not multilinear extensions before the prover indexes out of them.
+    pub fn try_add_product(
+        &mut self,
+        product: impl IntoIterator<Item = Rc<DenseMultilinearExtension<F>>>,
+        coefficient: C,
+    ) -> Result<(), crate::Error> {
         let product: Vec<Rc<DenseMultilinearExtension<F>>> = product.into_iter().collect();
+        for m in &product {
+            if m.num_vars != self.num_variables {
+                return Err(crate::Error::OtherError(
+                    "product has a multiplicand with wrong number of variables".into(),
+                ));
+            }
+            if m.num_vars >= usize::BITS as usize || m.evaluations.len() != 1 << m.num_vars {
+                return Err(crate::Error::OtherError(format!(
+                    "product has a multiplicand with {} evaluations for {} variables",
+                    m.evaluations.len(),
+                    m.num_vars
+                )));
+            }
+        }
+        // a constant round polynomial is sent with two evaluations, as if it had degree 1, so that
+        // the verifier reads `P(0)` and `P(1)` as for any other round
         let mut indexed_product = Vec::with_capacity(product.len());
-        assert!(!product.is_empty());
-        self.max_multiplicands = max(self.max_multiplicands, product.len());
+        self.max_multiplicands = max(self.max_multiplicands, max(product.len(), 1));
         for m in product {
-            assert_eq!(
-                m.num_vars, self.num_variables,
-                "product has a multiplicand with wrong number of variables"
-            );
             let m_ptr: *const DenseMultilinearExtension<F> = Rc::as_ptr(&m);
             if let Some(index) = self.raw_pointers_lookup_table.get(&m_ptr) {
                 indexed_product.push(*index)
@@ -93,18 +137,612 @@ impl<F: Field> ListOfProductsOfPolynomials<F> {
             }
         }
         self.products.push((coefficient, indexed_product));
+        Ok(())
+    }
+
+    /// Raise `max_multiplicands` to at least `degree`, so that the round polynomials are sent with
+    /// at least `degree + 1` evaluations.
+    ///
+    /// The polynomial and its sum are unchanged: the round polynomials keep their degree, and are
+    /// only evaluated at more points. This matches the polynomial info expected by a verifier of a
+    /// higher degree. The builders that derive a polynomial from this one, such as `merge` or
+    /// `restrict_variable`, keep the raised degree. Proving returns `Error::DegreeOverflow` if
+    /// `degree` is too large.
+    pub fn raise_degree(&mut self, degree: usize) {
+        self.max_multiplicands = max(self.max_multiplicands, degree);
     }
 
     /// Evaluate the polynomial at point `point`
-    pub fn evaluate(&self, point: &[F]) -> F {
+    pub fn evaluate(&self, point: &[F]) -> F
+    where
+        C: Copy + Into<F>,
+    {
         self.products
             .iter()
             .map(|(c, p)| {
-                *c * p
-                    .iter()
-                    .map(|&i| self.flattened_ml_extensions[i].evaluate(point).unwrap())
-                    .product::<F>()
+                (*c).into()
+                    * p.iter()
+                        .map(|&i| self.flattened_ml_extensions[i].evaluate(point).unwrap())
+                        .product::<F>()
             })
             .sum()
     }
+
+    /// number of random points compared by `represents_same`
+    pub const REPRESENTS_SAME_POINTS: usize = 4;
+
+    /// Returns whether `self` and `other` are probably the same polynomial, possibly with different
+    /// products, by comparing them at `REPRESENTS_SAME_POINTS` random points of `F^num_variables`.
+    ///
+    /// Different polynomials are never reported as the same, unless their difference vanishes at
+    /// all the points. Its degree is at most `d * num_variables`, where `d` is the larger
+    /// `max_multiplicands` of the two, so by the Schwartz-Zippel lemma this happens with
+    /// probability at most `(d * num_variables / |F|)^REPRESENTS_SAME_POINTS`. Each comparison
+    /// evaluates every multiplicand once, instead of comparing the polynomials at all points of a
+    /// grid large enough to determine them.
+    ///
+    /// Polynomials that only agree on the boolean hypercube, such as `f` and `f^2` for a boolean `f`,
+    /// are different.
+    pub fn represents_same<R: RngCore>(&self, other: &Self, rng: &mut R) -> bool
+    where
+        C: Copy + Into<F>,
+    {
+        if self.num_variables != other.num_variables {
+            return false;
+        }
+        (0..Self::REPRESENTS_SAME_POINTS).all(|_| {
+            let (point, value) = self.evaluate_random(rng);
+            other.evaluate(&point) == value
+        })
+    }
+
+    /// Returns a random point of `F^num_variables` and the evaluation of the polynomial there.
+    ///
+    /// A polynomial that is not zero is zero at the point with probability at most
+    /// `max_multiplicands * num_variables / |F|`, so a zero value is a cheap hint that the
+    /// polynomial is degenerate before proving it.
+    pub fn evaluate_random<R: RngCore>(&self, rng: &mut R) -> (Vec<F>, F)
+    where
+        C: Copy + Into<F>,
+    {
+        let point: Vec<F> = (0..self.num_variables).map(|_| F::rand(rng)).collect();
+        let value = self.evaluate(&point);
+        (point, value)
+    }
+}
+
+impl<F: Field> ListOfProductsOfPolynomials<F> {
+    /// Returns a Graphviz DOT graph of the structure of the list of products.
+    ///
+    /// There is one node per product, labelled with its coefficient and number of multiplicands,
+    /// and one node per multilinear extension, with an edge from each product to each of its
+    /// multiplicands. A multiplicand used several times in a product gets as many edges.
+    #[cfg(feature = "viz")]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph polynomial {\n");
+        for (i, (coefficient, product)) in self.products.iter().enumerate() {
+            dot.push_str(&format!(
+                "    p{i} [shape=box, label=\"product {i}\\ncoefficient: {coefficient}\\narity: {}\"];\n",
+                product.len()
+            ));
+        }
+        for (j, mle) in self.flattened_ml_extensions.iter().enumerate() {
+            dot.push_str(&format!(
+                "    m{j} [label=\"multiplicand {j}\\nnum_vars: {}\"];\n",
+                mle.num_vars
+            ));
+        }
+        for (i, (_, product)) in self.products.iter().enumerate() {
+            for j in product {
+                dot.push_str(&format!("    p{i} -> m{j};\n"));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[derive(Clone)]
+/// Stores the number of variables and max number of multiplicands of the added polynomial used by the prover.
+/// This data structures will is used as the verifier key.
+///
+/// `PolynomialInfo` has a fixed, canonical encoding, which is also what gets fed to the transcript:
+/// `max_multiplicands` as a little-endian `u64`, followed by `num_variables` as a little-endian `u64`
+/// (16 bytes in total). The encoding is the same in compressed and uncompressed mode.
+pub struct PolynomialInfo {
+    /// max number of multiplicands in each product
+    ///
+    /// A multiplicand repeated in a product, as by `ListOfProductsOfPolynomials::add_power`, is
+    /// counted once per occurrence, so this is the degree of the round polynomials.
+    pub max_multiplicands: usize,
+    /// number of variables of the polynomial
+    pub num_variables: usize,
+}
+
+impl PolynomialInfo {
+    /// size in bytes of the canonical encoding
+    pub const ENCODED_SIZE: usize = 16;
+
+    /// constraints for one permutation of the transcript sponge (Poseidon, width 3)
+    const SPONGE_PERMUTATION_CONSTRAINTS: usize = 300;
+
+    /// field elements absorbed per permutation of the transcript sponge
+    const SPONGE_RATE: usize = 2;
+
+    /// number of evaluations in each prover message, i.e. the degree of the round polynomials plus
+    /// one
+    ///
+    /// Returns `Error::DegreeOverflow` if it does not fit in a `usize`, which a deserialized info
+    /// can cause.
+    pub fn expected_round_message_len(&self) -> Result<usize, crate::Error> {
+        self.max_multiplicands
+            .checked_add(1)
+            .ok_or(crate::Error::DegreeOverflow)
+    }
+
+    /// Returns a rough estimate of the number of R1CS constraints needed to verify a proof for this
+    /// polynomial inside a circuit.
+    ///
+    /// The estimate is `num_variables * (interpolation + absorb)` where, for `d = max_multiplicands`,
+    /// * `interpolation = 4 * d` evaluates the round polynomial at the challenge from its `d + 1`
+    ///   evaluations, with prefix and suffix products of `r - i` and constant Lagrange weights,
+    /// * `absorb = ceil((d + 1) / 2) * 300` absorbs the round message into a Poseidon sponge of rate 2,
+    ///   at about 300 constraints per permutation; squeezing the challenge is free.
+    ///
+    /// Checking `P(0) + P(1)` against the expected sum is linear and costs nothing. Absorbing the
+    /// polynomial info and the final evaluation check are not counted.
+    ///
+    /// Returns `Error::DegreeOverflow` if the estimate does not fit in a `usize`.
+    pub fn recursion_constraint_estimate(&self) -> Result<usize, crate::Error> {
+        let d = self.max_multiplicands;
+        let interpolation = d.checked_mul(4);
+        let absorb = d
+            .checked_add(Self::SPONGE_RATE)
+            .map(|blocks| blocks / Self::SPONGE_RATE)
+            .and_then(|blocks| blocks.checked_mul(Self::SPONGE_PERMUTATION_CONSTRAINTS));
+        interpolation
+            .zip(absorb)
+            .and_then(|(interpolation, absorb)| interpolation.checked_add(absorb))
+            .and_then(|per_round| per_round.checked_mul(self.num_variables))
+            .ok_or(crate::Error::DegreeOverflow)
+    }
+}
+
+impl CanonicalSerialize for PolynomialInfo {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(&(self.max_multiplicands as u64).to_le_bytes())?;
+        writer.write_all(&(self.num_variables as u64).to_le_bytes())?;
+        Ok(())
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        Self::ENCODED_SIZE
+    }
+}
+
+impl Valid for PolynomialInfo {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for PolynomialInfo {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        let max_multiplicands = u64::from_le_bytes(buf) as usize;
+        reader.read_exact(&mut buf)?;
+        let num_variables = u64::from_le_bytes(buf) as usize;
+        Ok(PolynomialInfo {
+            max_multiplicands,
+            num_variables,
+        })
+    }
+}
+
+impl<F: Field> ListOfProductsOfPolynomials<F> {
+    /// largest power accepted by `try_add_power`
+    ///
+    /// The prover sends `k + 1` evaluations per round for a power `k`, and the product refers to
+    /// `f` `k` times, so larger powers are rejected before anything is allocated.
+    pub const MAX_POWER: usize = 1 << 20;
+
+    /// Returns an empty polynomial
+    pub fn new(num_variables: usize) -> Self {
+        ListOfProductsOfPolynomials {
+            max_multiplicands: 0,
+            num_variables,
+            products: Vec::new(),
+            flattened_ml_extensions: Vec::new(),
+            raw_pointers_lookup_table: HashMap::new(),
+        }
+    }
+
+    /// Add a product of multiplicands given by their evaluation tables, zero-padding each table to
+    /// the next power of two.
+    ///
+    /// Each padded table must have `2^num_variables` entries. The padded entries are zero, so each
+    /// product vanishes there, and the sum over the hypercube is the sum over the original rows.
+    ///
+    /// Panics with the error of `try_add_product_padded` if a table has a wrong size.
+    pub fn add_product_padded(&mut self, coefficient: F, tables: Vec<Vec<F>>) {
+        if let Err(e) = self.try_add_product_padded(coefficient, tables) {
+            panic!("{}", e);
+        }
+    }
+
+    /// same as `add_product_padded`, but returns an error instead of panicking
+    ///
+    /// Returns `Error::OtherError`, and leaves the polynomial unchanged, if a table does not pad to
+    /// `2^num_variables` entries.
+    pub fn try_add_product_padded(
+        &mut self,
+        coefficient: F,
+        tables: Vec<Vec<F>>,
+    ) -> Result<(), crate::Error> {
+        if self.num_variables >= usize::BITS as usize {
+            return Err(crate::Error::OtherError(format!(
+                "a table cannot have {} variables",
+                self.num_variables
+            )));
+        }
+        let num_evaluations = 1 << self.num_variables;
+        if let Some(table) = tables
+            .iter()
+            .find(|table| table.len().checked_next_power_of_two() != Some(num_evaluations))
+        {
+            return Err(crate::Error::OtherError(format!(
+                "a table of {} rows does not pad to {} variables",
+                table.len(),
+                self.num_variables
+            )));
+        }
+        let product: Vec<_> = tables
+            .into_iter()
+            .map(|mut table| {
+                table.resize(num_evaluations, F::zero());
+                Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    self.num_variables,
+                    table,
+                ))
+            })
+            .collect();
+        self.try_add_product(product, coefficient)
+    }
+
+    /// Add `coefficient * f^k` as a single product.
+    ///
+    /// `f` is stored only once, and the product refers to it `k` times, so the degree of the round
+    /// polynomials is at least `k`.
+    ///
+    /// Panics with the error of `try_add_power` if the product is malformed.
+    pub fn add_power(&mut self, coefficient: F, f: &DenseMultilinearExtension<F>, k: usize) {
+        if let Err(e) = self.try_add_power(coefficient, f, k) {
+            panic!("{}", e);
+        }
+    }
+
+    /// same as `add_power`, but returns an error instead of panicking
+    ///
+    /// Returns `Error::DegreeOverflow` if `k` is larger than `MAX_POWER`, before allocating the `k`
+    /// references to `f`.
+    pub fn try_add_power(
+        &mut self,
+        coefficient: F,
+        f: &DenseMultilinearExtension<F>,
+        k: usize,
+    ) -> Result<(), crate::Error> {
+        if k > Self::MAX_POWER {
+            return Err(crate::Error::DegreeOverflow);
+        }
+        let f = Rc::new(f.clone());
+        self.try_add_product(ark_std::iter::repeat(f).take(k), coefficient)
+    }
+
+    /// Multiply every product of the polynomial by `-1`.
+    pub fn negate(&mut self) {
+        for (coefficient, _) in self.products.iter_mut() {
+            *coefficient = -*coefficient;
+        }
+    }
+
+    /// Add all products of `other` to this polynomial.
+    ///
+    /// Multilinear extensions are shared with `other` rather than cloned. The degree is raised to
+    /// the one of `other`, even if `other` has fewer multiplicands in each product.
+    pub fn merge(&mut self, other: &ListOfProductsOfPolynomials<F>) {
+        assert_eq!(
+            other.num_variables, self.num_variables,
+            "polynomials have different number of variables"
+        );
+        self.raise_degree(other.max_multiplicands);
+        for (coefficient, product) in &other.products {
+            self.add_product(
+                product
+                    .iter()
+                    .map(|&i| other.flattened_ml_extensions[i].clone()),
+                *coefficient,
+            );
+        }
+    }
+
+    /// Add all products of `other` to this polynomial, each multiplied by `factor`.
+    ///
+    /// Multilinear extensions are shared with `other` rather than cloned, and the degree is raised as
+    /// by `merge`. Returns `Error::OtherError`, and leaves the polynomial unchanged, if `other` has a
+    /// different number of variables.
+    pub fn add_scaled(
+        &mut self,
+        factor: F,
+        other: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(), crate::Error> {
+        if other.num_variables != self.num_variables {
+            return Err(crate::Error::OtherError(
+                "polynomials have different number of variables".into(),
+            ));
+        }
+        self.raise_degree(other.max_multiplicands);
+        for (coefficient, product) in &other.products {
+            self.add_product(
+                product
+                    .iter()
+                    .map(|&i| other.flattened_ml_extensions[i].clone()),
+                factor * coefficient,
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the polynomial `Q(y) = P(shift * y)`, where `P` is this polynomial.
+    ///
+    /// Each multiplicand stays multilinear, and the sum of `Q` over {0,1}^`num_variables` equals the
+    /// sum of `P` over {0, `shift`}^`num_variables`.
+    pub fn scale_domain(&self, shift: F) -> Self {
+        let mut result = ListOfProductsOfPolynomials::new(self.num_variables);
+        result.raise_degree(self.max_multiplicands);
+        let scaled: Vec<_> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| {
+                let mut evaluations = mle.evaluations.clone();
+                for i in 0..self.num_variables {
+                    // at variable i, replace P(.., 1, ..) by P(.., shift, ..) = P(.., 0, ..) + shift * (P(.., 1, ..) - P(.., 0, ..))
+                    for lo in (0..evaluations.len()).filter(|k| k & (1 << i) == 0) {
+                        let hi = lo | (1 << i);
+                        evaluations[hi] =
+                            evaluations[lo] + shift * (evaluations[hi] - evaluations[lo]);
+                    }
+                }
+                Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    self.num_variables,
+                    evaluations,
+                ))
+            })
+            .collect();
+        for (coefficient, product) in &self.products {
+            result.add_product(product.iter().map(|&i| scaled[i].clone()), *coefficient);
+        }
+        result
+    }
+
+    /// Returns the polynomial in `num_variables - 1` variables obtained by fixing variable `index`
+    /// to `bit`.
+    ///
+    /// Multiplicands shared by several products stay shared.
+    pub fn restrict_variable(&self, index: usize, bit: bool) -> Self {
+        assert!(index < self.num_variables, "variable index out of range");
+        let nv = self.num_variables - 1;
+        let low_mask = (1 << index) - 1;
+        let restricted: Vec<_> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| {
+                let evaluations = (0..1 << nv)
+                    .map(|k: usize| {
+                        let high = (k >> index) << (index + 1);
+                        mle.evaluations[high | (usize::from(bit) << index) | (k & low_mask)]
+                    })
+                    .collect();
+                Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    nv,
+                    evaluations,
+                ))
+            })
+            .collect();
+        let mut result = ListOfProductsOfPolynomials::new(nv);
+        result.raise_degree(self.max_multiplicands);
+        for (coefficient, product) in &self.products {
+            result.add_product(product.iter().map(|&i| restricted[i].clone()), *coefficient);
+        }
+        result
+    }
+
+    /// Returns the polynomial whose variable `j` is variable `perm[j]` of this polynomial.
+    ///
+    /// `perm` must be a permutation of `0..num_variables`. Multiplicands shared by several products
+    /// stay shared.
+    pub fn permute_variables(&self, perm: &[usize]) -> Self {
+        assert_eq!(perm.len(), self.num_variables, "wrong permutation length");
+        let permuted: Vec<_> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| {
+                let evaluations = (0..mle.evaluations.len())
+                    .map(|y| {
+                        let x = perm
+                            .iter()
+                            .enumerate()
+                            .filter(|(j, _)| y & (1 << j) != 0)
+                            .fold(0, |x, (_, &i)| x | (1 << i));
+                        mle.evaluations[x]
+                    })
+                    .collect();
+                Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    self.num_variables,
+                    evaluations,
+                ))
+            })
+            .collect();
+        let mut result = ListOfProductsOfPolynomials::new(self.num_variables);
+        result.raise_degree(self.max_multiplicands);
+        for (coefficient, product) in &self.products {
+            result.add_product(product.iter().map(|&i| permuted[i].clone()), *coefficient);
+        }
+        result
+    }
+}
+
+impl<F: PrimeField> ListOfProductsOfPolynomials<F> {
+    /// Returns the same polynomial, with its coefficients and evaluations embedded in the extension
+    /// field `E` of `F`.
+    ///
+    /// Proving the lifted polynomial samples every challenge from `E`. See
+    /// `MLSumcheck::prove_with_wide_challenges`.
+    pub fn lift_to_extension<E: Field<BasePrimeField = F>>(
+        &self,
+    ) -> ListOfProductsOfPolynomials<E> {
+        let embed = |x: F| {
+            let mut elems = vec![F::zero(); E::extension_degree() as usize];
+            elems[0] = x;
+            E::from_base_prime_field_elems(&elems).expect("extension degree is correct")
+        };
+        let lifted: Vec<_> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| {
+                Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    self.num_variables,
+                    mle.evaluations.iter().map(|x| embed(*x)).collect(),
+                ))
+            })
+            .collect();
+        let mut result = ListOfProductsOfPolynomials::new(self.num_variables);
+        result.raise_degree(self.max_multiplicands);
+        for (coefficient, product) in &self.products {
+            result.add_product(
+                product.iter().map(|&i| lifted[i].clone()),
+                embed(*coefficient),
+            );
+        }
+        result
+    }
+}
+
+/// The multilinear extension `f(x) * g(y)` in `f.num_vars + g.num_vars` variables, where the variables
+/// of `f` come first.
+///
+/// This is proved by `MLSumcheck::prove_tensor_product` without materializing the `2^(n+m)` table:
+/// only `f` is folded during the first `n` rounds, and only `g` during the last `m` rounds.
+#[derive(Clone)]
+pub struct TensorProduct<F: Field> {
+    /// the factor on the first variables
+    pub f: DenseMultilinearExtension<F>,
+    /// the factor on the last variables
+    pub g: DenseMultilinearExtension<F>,
+}
+
+impl<F: Field> TensorProduct<F> {
+    /// Returns the tensor product of `f` and `g`.
+    pub fn new(f: DenseMultilinearExtension<F>, g: DenseMultilinearExtension<F>) -> Self {
+        Self { f, g }
+    }
+
+    /// number of variables of the tensor product
+    pub fn num_variables(&self) -> usize {
+        self.f.num_vars + self.g.num_vars
+    }
+
+    /// Extract the polynomial information, as if it were a single multiplicand.
+    pub fn info(&self) -> PolynomialInfo {
+        PolynomialInfo {
+            max_multiplicands: 1,
+            num_variables: self.num_variables(),
+        }
+    }
+
+    /// Returns the full table of the tensor product.
+    pub fn materialize(&self) -> DenseMultilinearExtension<F> {
+        let evaluations = self
+            .g
+            .evaluations
+            .iter()
+            .flat_map(|g| self.f.evaluations.iter().map(move |f| *f * g))
+            .collect();
+        DenseMultilinearExtension::from_evaluations_vec(self.num_variables(), evaluations)
+    }
+
+    /// Evaluate the tensor product at `point`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_variables(),
+            "wrong number of variables"
+        );
+        let (x, y) = point.split_at(self.f.num_vars);
+        self.f.evaluate(x).unwrap() * self.g.evaluate(y).unwrap()
+    }
+}
+
+/// Symbolic coefficient `rho^k`, where `rho` is the first challenge of the transcript.
+///
+/// A `ListOfProductsOfPolynomials<F, ChallengePower>` does not depend on `rho`, which is only
+/// drawn by `MLSumcheck::prove_self_scaling` once the sum of each power is bound to the transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChallengePower(pub usize);
+
+impl<F: Field> ListOfProductsOfPolynomials<F, ChallengePower> {
+    /// Returns the polynomial where the coefficient `ChallengePower(k)` of each product is
+    /// `challenge^k`.
+    ///
+    /// Multilinear extensions are shared rather than cloned.
+    pub fn materialize(&self, challenge: F) -> ListOfProductsOfPolynomials<F> {
+        ListOfProductsOfPolynomials {
+            max_multiplicands: self.max_multiplicands,
+            num_variables: self.num_variables,
+            products: self
+                .products
+                .iter()
+                .map(|(ChallengePower(k), product)| (challenge.pow([*k as u64]), product.clone()))
+                .collect(),
+            flattened_ml_extensions: self.flattened_ml_extensions.clone(),
+            raw_pointers_lookup_table: self.raw_pointers_lookup_table.clone(),
+        }
+    }
+
+    /// Returns `sums`, where `sums[k]` is the sum over the boolean hypercube of the products with
+    /// the coefficient `ChallengePower(k)`, and is zero if there is none.
+    ///
+    /// The sum of `materialize(challenge)` is $\sum_k challenge^k \cdot sums_k$. Returns
+    /// `Error::DegreeOverflow` if a power is larger than `ListOfProductsOfPolynomials::MAX_POWER`,
+    /// before `sums` is allocated.
+    pub fn sums_by_power(&self) -> Result<Vec<F>, crate::Error> {
+        let max_power = self.products.iter().map(|(ChallengePower(k), _)| *k).max();
+        let len = match max_power {
+            Some(k) if k > ListOfProductsOfPolynomials::<F>::MAX_POWER => {
+                return Err(crate::Error::DegreeOverflow)
+            }
+            Some(k) => k + 1,
+            None => 0,
+        };
+        let mut sums = vec![F::zero(); len];
+        for (ChallengePower(k), product) in &self.products {
+            sums[*k] += (0..1 << self.num_variables)
+                .map(|x| {
+                    product
+                        .iter()
+                        .map(|&i| self.flattened_ml_extensions[i].evaluations[x])
+                        .product::<F>()
+                })
+                .sum::<F>();
+        }
+        Ok(sums)
+    }
 }
@@ -0,0 +1,141 @@
+//! Equality check of two polynomials over the boolean hypercube.
+//!
+//! `f(x) = g(x)` for all `x` in {0,1}^`num_variables` is reduced to a zero-check of `f - g`: for a
+//! point `r` sampled from the transcript, the prover shows that
+//!
+//! $$\sum_{x\in\{0,1\}^n}eq(x, r)\cdot(f(x)-g(x)) = 0$$
+//!
+//! If `f - g` is not zero on the hypercube, the sum is non-zero with high probability over `r`.
+
+use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
+use crate::ml_sumcheck::{MLSumcheck, Proof};
+use crate::rng::FeedableRNG;
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::cmp::max;
+use ark_std::iter;
+use ark_std::marker::PhantomData;
+use ark_std::rc::Rc;
+use ark_std::vec::Vec;
+
+/// Equality check of two polynomials over the boolean hypercube
+pub struct EqualityCheck<F: Field>(#[doc(hidden)] PhantomData<F>);
+
+impl<F: Field> EqualityCheck<F> {
+    /// generate a proof that `f` and `g` agree on {0,1}^`num_variables`
+    ///
+    /// `f` and `g` must have the same number of variables, and at least one of them must be non-empty.
+    pub fn prove(
+        f: &ListOfProductsOfPolynomials<F>,
+        g: &ListOfProductsOfPolynomials<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<Proof<F>, crate::Error> {
+        let r = Self::sample_point(f, g, fs_rng)?;
+        let mut difference = g.clone();
+        difference.negate();
+        difference.merge(f);
+        let polynomial = zero_check_polynomial(&difference, &r);
+        MLSumcheck::prove_as_subprotocol(fs_rng, &polynomial).map(|r| r.0)
+    }
+
+    /// verify that `f` and `g` agree on {0,1}^`num_variables` using the proof
+    ///
+    /// The final check evaluates `f` and `g` at the subclaim point, so the verifier needs
+    /// (oracle) access to both polynomials.
+    pub fn verify(
+        f: &ListOfProductsOfPolynomials<F>,
+        g: &ListOfProductsOfPolynomials<F>,
+        proof: &Proof<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(), crate::Error> {
+        let r = Self::sample_point(f, g, fs_rng)?;
+        let polynomial_info = PolynomialInfo {
+            max_multiplicands: max(f.max_multiplicands, g.max_multiplicands) + 1,
+            num_variables: f.num_variables,
+        };
+        let subclaim =
+            MLSumcheck::verify_as_subprotocol(fs_rng, &polynomial_info, F::zero(), proof)?;
+        let point = &subclaim.point;
+        let actual = eq_eval(&r, point) * (f.evaluate(point) - g.evaluate(point));
+        if actual != subclaim.expected_evaluation {
+            return Err(crate::Error::Reject(Some(
+                "f(r) - g(r) is not consistent with the subclaim".into(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// bind `f` and `g` to the transcript, and sample the point `r` of the zero-check
+    fn sample_point(
+        f: &ListOfProductsOfPolynomials<F>,
+        g: &ListOfProductsOfPolynomials<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<Vec<F>, crate::Error> {
+        assert_eq!(
+            f.num_variables, g.num_variables,
+            "polynomials have different number of variables"
+        );
+        fs_rng.feed(&f.info())?;
+        fs_rng.feed(&g.info())?;
+        let mut r = Vec::with_capacity(f.num_variables);
+        for _ in 0..f.num_variables {
+            r.push(F::rand(fs_rng));
+        }
+        Ok(r)
+    }
+}
+
+/// multiply every product of `polynomial` by `eq(x, r)`
+pub(crate) fn zero_check_polynomial<F: Field>(
+    polynomial: &ListOfProductsOfPolynomials<F>,
+    r: &[F],
+) -> ListOfProductsOfPolynomials<F> {
+    let eq_x_r = Rc::new(build_eq_x_r(r));
+    let mut result = ListOfProductsOfPolynomials::new(polynomial.num_variables);
+    result.raise_degree(polynomial.max_multiplicands.saturating_add(1));
+    for (coefficient, product) in &polynomial.products {
+        result.add_product(
+            iter::once(eq_x_r.clone()).chain(
+                product
+                    .iter()
+                    .map(|&i| polynomial.flattened_ml_extensions[i].clone()),
+            ),
+            *coefficient,
+        );
+    }
+    result
+}
+
+/// build the multilinear extension of `eq(x, r)` in evaluation form over {0,1}^`r.len()`
+pub fn build_eq_x_r<F: Field>(r: &[F]) -> DenseMultilinearExtension<F> {
+    let mut evaluations = vec![F::one()];
+    for r_i in r {
+        // the new variable is the most significant bit of the index
+        let high: Vec<F> = evaluations.iter().map(|e| *e * r_i).collect();
+        evaluations
+            .iter_mut()
+            .zip(high.iter())
+            .for_each(|(e, h)| *e -= h);
+        evaluations.extend(high);
+    }
+    DenseMultilinearExtension::from_evaluations_vec(r.len(), evaluations)
+}
+
+/// table of `eq(r, x)` at every point `x` of {0,1}^`r.len()`
+///
+/// The entry at index `i` is `eq(r, x)` for the point `x` with `x_j` in bit `j` of `i`, so once the
+/// table is built, each evaluation of `eq(r, ·)` at a hypercube point is a lookup instead of the
+/// `O(r.len())` product of `eq_eval`. The table holds `2^r.len()` field elements, so it only pays off
+/// when `r` is fixed across many evaluations, as in the final checks of GKR.
+pub fn precompute_eq_table<F: Field>(r: &[F]) -> Vec<F> {
+    build_eq_x_r(r).evaluations
+}
+
+/// evaluate `eq(x, y) = \prod_i (x_i y_i + (1 - x_i)(1 - y_i))`
+pub fn eq_eval<F: Field>(x: &[F], y: &[F]) -> F {
+    assert_eq!(x.len(), y.len());
+    x.iter()
+        .zip(y.iter())
+        .map(|(&x_i, &y_i)| x_i * y_i + (F::one() - x_i) * (F::one() - y_i))
+        .product()
+}
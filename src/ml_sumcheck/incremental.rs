@@ -0,0 +1,99 @@
+//! Verifier that receives the proof one round at a time.
+
+use crate::ml_sumcheck::data_structures::PolynomialInfo;
+use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::ml_sumcheck::protocol::verifier::{feed_round_message, SubClaim, VerifierState};
+use crate::ml_sumcheck::protocol::IPForMLSumcheck;
+use crate::rng::{Blake2s512Rng, FeedableRNG, SetupRNG};
+use ark_ff::Field;
+
+/// Number of rounds received by an `IncrementalVerifier`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyProgress {
+    /// number of rounds received so far
+    pub rounds_done: usize,
+    /// total number of rounds, i.e. the number of variables of the polynomial
+    pub num_variables: usize,
+}
+
+impl VerifyProgress {
+    /// whether all rounds have been received
+    pub fn is_complete(&self) -> bool {
+        self.rounds_done == self.num_variables
+    }
+}
+
+/// Verifier for proofs generated by `MLSumcheck::prove`, fed one prover message at a time.
+///
+/// The verifier owns its transcript and samples the challenges itself, so the result is the same as
+/// `MLSumcheck::verify` on the whole proof.
+pub struct IncrementalVerifier<F: Field> {
+    polynomial_info: PolynomialInfo,
+    claimed_sum: F,
+    fs_rng: Blake2s512Rng,
+    verifier_state: VerifierState<F>,
+    rounds_done: usize,
+}
+
+impl<F: Field> IncrementalVerifier<F> {
+    /// start verifying that `claimed_sum` is the sum of a polynomial described by `polynomial_info`
+    pub fn new(polynomial_info: &PolynomialInfo, claimed_sum: F) -> Self {
+        Self {
+            polynomial_info: polynomial_info.clone(),
+            claimed_sum,
+            fs_rng: Blake2s512Rng::setup(),
+            verifier_state: IPForMLSumcheck::verifier_init(polynomial_info),
+            rounds_done: 0,
+        }
+    }
+
+    /// receive the prover message of the next round
+    ///
+    /// Returns `Error::Reject` if all rounds have already been received, or if the message does not
+    /// have `max_multiplicands + 1` evaluations.
+    pub fn feed_round(&mut self, msg: ProverMsg<F>) -> Result<VerifyProgress, crate::Error> {
+        if self.rounds_done == self.polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(
+                "all rounds have been received".into(),
+            )));
+        }
+        let num_evaluations = msg.evaluations.len();
+        if num_evaluations < 2 || num_evaluations - 1 != self.polynomial_info.max_multiplicands {
+            return Err(crate::Error::Reject(Some(format!(
+                "incorrect number of evaluations at round {}",
+                self.rounds_done
+            ))));
+        }
+        feed_round_message(
+            &mut self.fs_rng,
+            &self.polynomial_info,
+            &msg,
+            self.rounds_done == 0,
+        )?;
+        IPForMLSumcheck::verify_round(msg, &mut self.verifier_state, &mut self.fs_rng);
+        self.rounds_done += 1;
+        Ok(self.progress())
+    }
+
+    /// number of rounds received so far
+    pub fn progress(&self) -> VerifyProgress {
+        VerifyProgress {
+            rounds_done: self.rounds_done,
+            num_variables: self.polynomial_info.num_variables,
+        }
+    }
+
+    /// check all rounds, and generate the subclaim
+    ///
+    /// Returns `Error::WrongProofLength` if some rounds are missing.
+    pub fn finish(self) -> Result<SubClaim<F>, crate::Error> {
+        let progress = self.progress();
+        if !progress.is_complete() {
+            return Err(crate::Error::WrongProofLength {
+                expected: progress.num_variables,
+                got: progress.rounds_done,
+            });
+        }
+        IPForMLSumcheck::check_and_generate_subclaim(self.verifier_state, self.claimed_sum)
+    }
+}
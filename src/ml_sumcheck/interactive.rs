@@ -0,0 +1,52 @@
+//! Prover that produces the proof one round at a time.
+
+use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use crate::ml_sumcheck::protocol::prover::{ProverMsg, ProverState};
+use crate::ml_sumcheck::protocol::verifier::VerifierMsg;
+use crate::ml_sumcheck::protocol::IPForMLSumcheck;
+use crate::ml_sumcheck::MLSumcheck;
+use ark_ff::Field;
+
+/// Prover for an interactive sumcheck, driven one verifier message at a time.
+///
+/// Unlike `IPForMLSumcheck::prove_round`, which panics when its state machine is misused, every
+/// misuse is reported as an error by `IPForMLSumcheck::try_prove_round` and leaves the prover
+/// unchanged.
+pub struct SumcheckProver<F: Field> {
+    prover_state: ProverState<F>,
+}
+
+impl<F: Field> SumcheckProver<F> {
+    /// start proving the sum of `polynomial`
+    ///
+    /// Returns `Error::ConstantPolynomial` if the polynomial has no variable, and
+    /// `Error::EmptyPolynomial` if it has no product.
+    pub fn new(polynomial: &ListOfProductsOfPolynomials<F>) -> Result<Self, crate::Error> {
+        MLSumcheck::check_provable(polynomial)?;
+        Ok(Self {
+            prover_state: IPForMLSumcheck::prover_init(polynomial),
+        })
+    }
+
+    /// receive the challenge of the previous round, and return the prover message of the next round
+    ///
+    /// `verifier_msg` is `None` for the first round, and the challenge of the previous round
+    /// afterwards. Returns `Error::ProtocolFinished` once all `num_variables` rounds are done, and
+    /// `Error::OtherError` if `verifier_msg` is missing or unexpected.
+    pub fn next_round(
+        &mut self,
+        verifier_msg: &Option<VerifierMsg<F>>,
+    ) -> Result<ProverMsg<F>, crate::Error> {
+        IPForMLSumcheck::try_prove_round(&mut self.prover_state, verifier_msg)
+    }
+
+    /// whether the prover messages of all rounds have been produced
+    pub fn is_finished(&self) -> bool {
+        self.prover_state.round == self.prover_state.num_vars
+    }
+
+    /// Returns the state of the prover.
+    pub fn into_state(self) -> ProverState<F> {
+        self.prover_state
+    }
+}
@@ -0,0 +1,103 @@
+//! Binary Merkle tree over the round messages of a proof.
+//!
+//! Leaf `i` is the Blake2s hash of a `0` byte followed by the uncompressed serialization of the
+//! message of round `i`. An inner node is the hash of a `1` byte followed by its two children, so
+//! that a node cannot be opened as a leaf. The leaves are padded with zero digests up to a power of
+//! two.
+
+use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::ml_sumcheck::Proof;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use blake2::{Blake2s, Digest};
+
+/// a round message with the path from its leaf to the root
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MerkleOpening<F: Field> {
+    /// the message of the round
+    pub message: ProverMsg<F>,
+    /// the siblings of the nodes from the leaf to the root, excluded
+    pub path: Vec<[u8; 32]>,
+}
+
+impl<F: Field> MerkleOpening<F> {
+    /// Returns whether `message` is leaf `index` of the tree of root `root`.
+    pub fn verify(&self, index: usize, root: &[u8; 32]) -> bool {
+        if self.path.len() >= usize::BITS as usize || index >> self.path.len() != 0 {
+            return false;
+        }
+        let mut node = hash_leaf(&self.message);
+        for (level, sibling) in self.path.iter().enumerate() {
+            node = if (index >> level) & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+        }
+        node == *root
+    }
+}
+
+/// Returns the root of the tree of the round messages of `proof`.
+pub fn merkle_root<F: Field>(proof: &Proof<F>) -> [u8; 32] {
+    let levels = tree_levels(proof);
+    levels[levels.len() - 1][0]
+}
+
+/// Returns the opening of each round message of `proof`, in the order of the rounds.
+pub fn merkle_openings<F: Field>(proof: &Proof<F>) -> Vec<MerkleOpening<F>> {
+    let levels = tree_levels(proof);
+    proof
+        .iter()
+        .enumerate()
+        .map(|(index, message)| MerkleOpening {
+            message: message.clone(),
+            path: levels[..levels.len() - 1]
+                .iter()
+                .enumerate()
+                .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+                .collect(),
+        })
+        .collect()
+}
+
+/// every level of the tree, from the padded leaves to the root
+fn tree_levels<F: Field>(proof: &Proof<F>) -> Vec<Vec<[u8; 32]>> {
+    let mut leaves: Vec<[u8; 32]> = proof.iter().map(hash_leaf).collect();
+    leaves.resize(proof.len().next_power_of_two(), [0u8; 32]);
+    let mut levels = vec![leaves];
+    while levels[levels.len() - 1].len() > 1 {
+        let parents = levels[levels.len() - 1]
+            .chunks_exact(2)
+            .map(|pair| hash_node(&pair[0], &pair[1]))
+            .collect();
+        levels.push(parents);
+    }
+    levels
+}
+
+fn hash_leaf<F: Field>(message: &ProverMsg<F>) -> [u8; 32] {
+    let mut buf = Vec::new();
+    message
+        .serialize_uncompressed(&mut buf)
+        .expect("serializing into a vector does not fail");
+    let mut hasher = Blake2s::new();
+    hasher.update([0u8]);
+    hasher.update(&buf);
+    finalize(hasher)
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Blake2s::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    finalize(hasher)
+}
+
+fn finalize(hasher: Blake2s) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&hasher.finalize());
+    digest
+}
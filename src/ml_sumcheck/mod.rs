@@ -1,19 +1,53 @@
 //! Sumcheck Protocol for multilinear extension
 
-use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
-use crate::ml_sumcheck::protocol::prover::{ProverMsg, ProverState};
-use crate::ml_sumcheck::protocol::verifier::SubClaim;
+use crate::ml_sumcheck::data_structures::{
+    ChallengePower, ListOfProductsOfPolynomials, PolynomialInfo, TensorProduct,
+};
+use crate::ml_sumcheck::equality_check::{build_eq_x_r, eq_eval, EqualityCheck};
+use crate::ml_sumcheck::merkle::MerkleOpening;
+use crate::ml_sumcheck::protocol::oracle::{BroadcastMLE, MultilinearOracle};
+use crate::ml_sumcheck::protocol::prover::{
+    ProofEncoding, ProverMsg, ProverState, ProverWorkspace,
+};
+#[cfg(feature = "stats")]
+use crate::ml_sumcheck::protocol::verifier::VerifyStats;
+use crate::ml_sumcheck::protocol::verifier::{
+    check_num_evaluations, coefficients_to_evaluations, evaluations_to_coefficients,
+    feed_round_message, horner_evaluate, interpolate_uni_poly, round_check_failed, SubClaim,
+    VerifierHandoff, VerifierMsg, VerifierState, VerifyOptions,
+};
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
-use crate::rng::{Blake2s512Rng, FeedableRNG};
-use ark_ff::Field;
+use crate::rng::{
+    BatchChallengeSource, Blake2s512Rng, ChallengeSource, FeedableRNG, ProverTranscript, SetupRNG,
+    VerifierTranscript,
+};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, Write};
+use ark_std::cmp::{max, min};
+use ark_std::iter;
 use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::rc::Rc;
 use ark_std::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 pub mod protocol;
 
+pub mod accumulator;
+pub mod commitment;
 pub mod data_structures;
+pub mod equality_check;
+pub mod incremental;
+pub mod interactive;
+pub mod merkle;
 #[cfg(test)]
 mod test;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
 
 /// Sumcheck for products of multilinear polynomial
 pub struct MLSumcheck<F: Field>(#[doc(hidden)] PhantomData<F>);
@@ -21,10 +55,129 @@ pub struct MLSumcheck<F: Field>(#[doc(hidden)] PhantomData<F>);
 /// proof generated by prover
 pub type Proof<F> = Vec<ProverMsg<F>>;
 
+/// proof generated by `MLSumcheck::prove_power_sum`
+#[derive(Clone)]
+pub struct PowerSumProof<F: Field> {
+    /// one sumcheck proof per squaring layer, starting from the highest power
+    pub proofs: Vec<Proof<F>>,
+    /// the evaluation of the table of each layer at the point of its sumcheck
+    pub evaluations: Vec<F>,
+}
+
+/// profile of a proof measured by `MLSumcheck::prove_dry_run`
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct ProveProfile {
+    /// number of rounds, i.e. of prover messages
+    pub rounds: usize,
+    /// number of field elements in each prover message
+    pub round_message_len: usize,
+    /// time spent proving, including the transcript
+    pub elapsed: std::time::Duration,
+}
+
+/// record of a successful verification, returned by `MLSumcheck::verify_with_certificate`
+///
+/// The certificate binds the claimed sum to the subclaim and to the digest of the transcript of
+/// `MLSumcheck::verify_with_digest`, so that a third party holding the proof can audit the
+/// verification later.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerificationCertificate<F: Field> {
+    /// the sum the proof was verified against
+    pub claimed_sum: F,
+    /// the point of the subclaim
+    pub subclaim_point: Vec<F>,
+    /// the expected evaluation of the subclaim
+    pub expected_evaluation: F,
+    /// the digest of the transcript after the last round
+    pub transcript_digest: [u8; 32],
+}
+
+impl<F: Field> VerificationCertificate<F> {
+    /// Returns whether the certificate is the one of the verification of `proof` for
+    /// `polynomial_info`.
+    ///
+    /// The Fiat-Shamir transcript of the proof is replayed, which takes `O(num_variables * degree)`
+    /// field operations and does not need the polynomial. As for `verify`, the subclaim itself is
+    /// not checked against the multiplicands.
+    pub fn check(&self, proof: &Proof<F>, polynomial_info: &PolynomialInfo) -> bool {
+        match MLSumcheck::verify_with_digest(polynomial_info, self.claimed_sum, proof) {
+            Ok((subclaim, digest)) => {
+                subclaim.point == self.subclaim_point
+                    && subclaim.expected_evaluation == self.expected_evaluation
+                    && digest == self.transcript_digest
+            }
+            Err(_) => false,
+        }
+    }
+}
+
 impl<F: Field> MLSumcheck<F> {
     /// extract sum from the proof
+    ///
+    /// The sum is `P(0) + P(1)` for the first round polynomial `P`. Round polynomials have degree
+    /// at least 1, since a polynomial of empty products is proved as degree 1, so every message has
+    /// at least two evaluations. Panics with the error of `try_extract_sum` if the proof is empty,
+    /// or if its first message has fewer than two evaluations.
     pub fn extract_sum(proof: &Proof<F>) -> F {
-        proof[0].evaluations[0] + proof[0].evaluations[1]
+        match Self::try_extract_sum(proof) {
+            Ok(sum) => sum,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// same as `extract_sum`, but returns `Error::Reject` instead of panicking if the proof is
+    /// empty, or if its first message has fewer than two evaluations
+    pub fn try_extract_sum(proof: &Proof<F>) -> Result<F, crate::Error> {
+        let first = proof
+            .first()
+            .ok_or_else(|| crate::Error::Reject(Some("the proof has no round".into())))?;
+        match first.evaluations.as_slice() {
+            [p0, p1, ..] => Ok(*p0 + p1),
+            _ => Err(crate::Error::Reject(Some(
+                "the first prover message has fewer than two evaluations".into(),
+            ))),
+        }
+    }
+
+    /// find the first position where two proofs differ
+    ///
+    /// Returns the `(round_index, evaluation_index)` of the first differing field element, or `None`
+    /// if both proofs are identical. When one proof (or one round message) is a strict prefix of the
+    /// other, the position right after the common prefix is reported.
+    pub fn first_divergence(a: &Proof<F>, b: &Proof<F>) -> Option<(usize, usize)> {
+        for (round, (msg_a, msg_b)) in a.iter().zip(b.iter()).enumerate() {
+            let (evals_a, evals_b) = (&msg_a.evaluations, &msg_b.evaluations);
+            if let Some(i) = evals_a.iter().zip(evals_b.iter()).position(|(x, y)| x != y) {
+                return Some((round, i));
+            }
+            if evals_a.len() != evals_b.len() {
+                return Some((round, min(evals_a.len(), evals_b.len())));
+            }
+        }
+        if a.len() != b.len() {
+            return Some((min(a.len(), b.len()), 0));
+        }
+        None
+    }
+
+    /// check that every field element of `proof` is in canonical form
+    ///
+    /// Deserializing a `Proof` already rejects encodings of integers that are not smaller than the
+    /// modulus, so two byte strings never decode to the same proof. This checks a proof built in
+    /// memory, by re-encoding each element and decoding it with validation. Returns
+    /// `Error::SerializationError` on the first element that does not round-trip. `Proof` is a type
+    /// alias, so the check is defined here.
+    pub fn validate_canonical(proof: &Proof<F>) -> Result<(), crate::Error> {
+        let mut bytes = Vec::new();
+        for element in proof.iter().flat_map(|msg| msg.evaluations.iter()) {
+            bytes.clear();
+            element.serialize_compressed(&mut bytes)?;
+            if F::deserialize_compressed(&bytes[..])? != *element {
+                return Err(crate::Error::SerializationError);
+            }
+        }
+        Ok(())
     }
 
     /// generate proof of the sum of polynomial over {0,1}^`num_vars`
@@ -39,144 +192,2163 @@ impl<F: Field> MLSumcheck<F> {
     /// The resulting polynomial is
     ///
     /// $$\sum_{i=0}^{n}C_i\cdot\prod_{j=0}^{m_i}P_{ij}$$
-    pub fn prove(polynomial: &ListOfProductsOfPolynomials<F>) -> Result<Proof<F>, crate::Error> {
+    ///
+    /// The coefficients may have any type `C` converting into `F`, and are converted when the prover
+    /// is initialized.
+    ///
+    /// Returns `Error::ConstantPolynomial` if the polynomial has no variable, and
+    /// `Error::EmptyPolynomial` if it has no product.
+    ///
+    /// The prove and verify functions of `MLSumcheck` do not panic on malformed polynomials, proofs or
+    /// polynomial infos, and return an error instead. The round-by-round functions of `IPForMLSumcheck`
+    /// panic when their state machine is misused, and `IPForMLSumcheck::try_prove_round` and
+    /// `IPForMLSumcheck::try_sample_round` return an error instead.
+    pub fn prove<C: Copy + Into<F>>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+    ) -> Result<Proof<F>, crate::Error> {
         let mut fs_rng = Blake2s512Rng::setup();
         Self::prove_as_subprotocol(&mut fs_rng, polynomial).map(|r| r.0)
     }
 
+    /// same as `prove`, but also returns a 32-byte digest squeezed from the transcript after the last
+    /// round, which matches the digest of `verify_with_digest`
+    pub fn prove_with_digest(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(Proof<F>, [u8; 32]), crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let (proof, _) = Self::prove_as_subprotocol(&mut fs_rng, polynomial)?;
+        let mut digest = [0u8; 32];
+        fs_rng.fill_bytes(&mut digest);
+        Ok((proof, digest))
+    }
+
+    /// same as `prove`, but also returns the multiplicands after each round
+    ///
+    /// The `i`-th entry of the trace holds the tables of the multiplicands once the first `i + 1`
+    /// variables are fixed to the challenges, so the tables of the last entry have a single
+    /// evaluation. This keeps every intermediate table, and is only meant for inspection.
+    #[cfg(feature = "fold-trace")]
+    pub fn prove_with_trace(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(Proof<F>, Vec<Vec<DenseMultilinearExtension<F>>>), crate::Error> {
+        Self::check_provable(polynomial)?;
+        let polynomial_info = polynomial.info();
+        let mut fs_rng = Blake2s512Rng::setup();
+        let mut prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut proof = Vec::with_capacity(polynomial.num_variables);
+        let mut trace = Vec::with_capacity(polynomial.num_variables);
+        let mut verifier_msg = None;
+        for round in 0..polynomial.num_variables {
+            let prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+            if round > 0 {
+                // the prover has just fixed the variable of the previous round
+                trace.push(prover_state.flattened_ml_extensions.clone());
+            }
+            feed_round_message(&mut fs_rng, &polynomial_info, &prover_msg, round == 0)?;
+            proof.push(prover_msg);
+            verifier_msg = Some(IPForMLSumcheck::sample_round(&mut fs_rng));
+        }
+        // the prover never fixes the last variable, so its tables are folded here
+        if let Some(msg) = verifier_msg {
+            let mut tables = prover_state.flattened_ml_extensions;
+            tables.iter_mut().for_each(|t| t.fold(msg.randomness));
+            trace.push(tables);
+        }
+        Ok((proof, trace))
+    }
+
     /// This function does the same thing as `prove`, but it uses a `FeedableRNG` as the transcript/to generate the
     /// verifier challenges. Additionally, it returns the prover's state in addition to the proof.
     /// Both of these allow this sumcheck to be better used as a part of a larger protocol.
-    pub fn prove_as_subprotocol(
+    ///
+    /// # Challenge dependency
+    ///
+    /// The challenge of round `i` is sampled from the transcript after the prover message of round `i` is
+    /// fed, and the message of round `i + 1` depends on that challenge. The rounds therefore cannot be
+    /// reordered, nor can the messages be computed ahead with precomputed challenges: a proof built that
+    /// way is rejected, since the verifier derives its challenges from the messages it receives. The
+    /// only feeds not separated by a challenge are the polynomial info and the first message, which are
+    /// fed together with `FeedableRNG::feed_batch`.
+    pub fn prove_as_subprotocol<C: Copy + Into<F>>(
         fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::prove_with_round_zero(fs_rng, polynomial, None)
+    }
+
+    /// same as `prove_as_subprotocol`, but only accepts the prover side of a transcript
+    pub fn prove_with_transcript<R: FeedableRNG<Error = crate::Error>>(
+        transcript: &mut ProverTranscript<R>,
         polynomial: &ListOfProductsOfPolynomials<F>,
     ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
-        fs_rng.feed(&polynomial.info())?;
+        Self::prove_as_subprotocol(transcript.as_rng_mut(), polynomial)
+    }
+
+    /// same as `prove_as_subprotocol`, but feeds `commitment` to the transcript first
+    ///
+    /// `commitment` is typically computed by a `commitment::CommitmentScheme`. Since every challenge
+    /// depends on it, the proof is bound to the committed polynomial. The proof is checked by
+    /// `verify_committed` with the same commitment.
+    pub fn prove_committed<C: CanonicalSerialize>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        commitment: &C,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        fs_rng.feed(commitment)?;
+        Self::prove_as_subprotocol(fs_rng, polynomial)
+    }
+
+    /// This function does the same thing as `prove_as_subprotocol`, but reuses the first prover message
+    /// `round_zero` computed by `ProverState::precompute_round_zero` instead of recomputing it.
+    ///
+    /// The first prover message does not depend on any challenge, so it can be cached when the same
+    /// polynomial is proved under different transcripts. The cache is only valid for the polynomial it
+    /// was computed from: using a message of another polynomial results in an invalid proof.
+    pub fn prove_as_subprotocol_with_round_zero(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        round_zero: &ProverMsg<F>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::prove_with_round_zero(fs_rng, polynomial, Some(round_zero))
+    }
+
+    /// same as `prove_as_subprotocol`, but the prover tables are taken from `workspace` and given
+    /// back to it afterwards
+    ///
+    /// This avoids allocating the tables for every proof when proving many polynomials of the same
+    /// shape.
+    pub fn prove_in_workspace(
+        workspace: &mut ProverWorkspace<F>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<Proof<F>, crate::Error> {
+        Self::check_provable(polynomial)?;
+        let prover_state = IPForMLSumcheck::prover_init_in_workspace(polynomial, workspace);
+        let (proof, prover_state) =
+            Self::run_prover_rounds(fs_rng, &polynomial.info(), prover_state, None)?;
+        prover_state.release_into(workspace);
+        Ok(proof)
+    }
+
+    fn prove_with_round_zero<C: Copy + Into<F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+        round_zero: Option<&ProverMsg<F>>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::check_provable(polynomial)?;
+        let prover_state = IPForMLSumcheck::prover_init(polynomial);
+        Self::run_prover_rounds(fs_rng, &polynomial.info(), prover_state, round_zero)
+    }
+
+    /// Returns an error if `polynomial` cannot be proved.
+    ///
+    /// The fields of `ListOfProductsOfPolynomials` are public, so the polynomial is checked again as
+    /// a whole: every product must refer to existing multiplicands and have at most
+    /// `max_multiplicands` of them, and every multiplicand must have `num_variables` variables and
+    /// `2^num_variables` evaluations. Otherwise `Error::OtherError` is returned, before the prover
+    /// indexes out of a table.
+    pub(crate) fn check_provable<C>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+    ) -> Result<(), crate::Error> {
+        if polynomial.num_variables == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        if polynomial.num_products() == 0 {
+            return Err(crate::Error::EmptyPolynomial);
+        }
+        let nv = polynomial.num_variables;
+        for (i, table) in polynomial.flattened_ml_extensions.iter().enumerate() {
+            if table.num_vars != nv
+                || nv >= usize::BITS as usize
+                || table.evaluations.len() != 1 << nv
+            {
+                return Err(crate::Error::OtherError(format!(
+                    "multiplicand {} has {} evaluations for {} variables, but the polynomial has {} variables",
+                    i,
+                    table.evaluations.len(),
+                    table.num_vars,
+                    nv
+                )));
+            }
+        }
+        // the prover accumulates `max_multiplicands + 1` evaluations per product
+        polynomial
+            .max_multiplicands
+            .checked_add(1)
+            .and_then(|width| width.checked_mul(polynomial.num_products()))
+            .ok_or(crate::Error::DegreeOverflow)?;
+        let num_multiplicands = polynomial.flattened_ml_extensions.len();
+        for (i, (_, product)) in polynomial.products.iter().enumerate() {
+            if let Some(&j) = product.iter().find(|&&j| j >= num_multiplicands) {
+                return Err(crate::Error::OtherError(format!(
+                    "product {} refers to multiplicand {}, but there are {}",
+                    i, j, num_multiplicands
+                )));
+            }
+            if max(product.len(), 1) > polynomial.max_multiplicands {
+                return Err(crate::Error::OtherError(format!(
+                    "product {} has {} multiplicands, more than max_multiplicands = {}",
+                    i,
+                    product.len(),
+                    polynomial.max_multiplicands
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `Error::WrongProofLength` unless `proof` has exactly `num_rounds` rounds.
+    fn check_proof_length(proof: &[ProverMsg<F>], num_rounds: usize) -> Result<(), crate::Error> {
+        if proof.len() != num_rounds {
+            return Err(crate::Error::WrongProofLength {
+                expected: num_rounds,
+                got: proof.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// run all rounds of the prover initialized from a polynomial described by `polynomial_info`
+    fn run_prover_rounds<O: MultilinearOracle<F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        prover_state: ProverState<F, O>,
+        round_zero: Option<&ProverMsg<F>>,
+    ) -> Result<(Proof<F>, ProverState<F, O>), crate::Error> {
+        let mut prover_msgs = Vec::with_capacity(polynomial_info.num_variables);
+        let prover_state = Self::emit_prover_rounds(
+            fs_rng,
+            polynomial_info,
+            prover_state,
+            round_zero,
+            true,
+            |msg| {
+                prover_msgs.push(msg);
+                Ok(())
+            },
+        )?;
+        Ok((prover_msgs, prover_state))
+    }
+
+    /// same as `run_prover_rounds`, but hands each prover message to `emit` once it is fed to the
+    /// transcript instead of collecting them
+    ///
+    /// Unless `absorb_info` is set, `polynomial_info` is assumed to be already fed, and the first
+    /// message is fed alone.
+    fn emit_prover_rounds<O: MultilinearOracle<F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        mut prover_state: ProverState<F, O>,
+        round_zero: Option<&ProverMsg<F>>,
+        absorb_info: bool,
+        mut emit: impl FnMut(ProverMsg<F>) -> Result<(), crate::Error>,
+    ) -> Result<ProverState<F, O>, crate::Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "prove",
+            num_variables = polynomial_info.num_variables,
+            max_multiplicands = polynomial_info.max_multiplicands
+        )
+        .entered();
+        let mut verifier_msg = None;
+        for round in 0..polynomial_info.num_variables {
+            #[cfg(feature = "tracing")]
+            let _round_span = tracing::trace_span!("prove_round", round).entered();
+            let prover_msg = match (round, round_zero) {
+                (0, Some(msg)) => {
+                    prover_state.round += 1;
+                    msg.clone()
+                }
+                _ => IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg),
+            };
+            feed_round_message(
+                fs_rng,
+                polynomial_info,
+                &prover_msg,
+                round == 0 && absorb_info,
+            )?;
+            emit(prover_msg)?;
+            verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
+        }
+        if let Some(msg) = verifier_msg {
+            prover_state.randomness.push(msg.randomness);
+        }
+        Ok(prover_state)
+    }
+
+    /// same as `prove_as_subprotocol`, for a claimed sum derived from the transcript by `sum_oracle`
+    ///
+    /// The transcript is used in this order, which `verify_with_sum_oracle` follows:
+    /// 1. the polynomial info is fed,
+    /// 2. `sum_oracle` is called on the transcript, and may feed it and sample challenges from it, for
+    ///    instance to open a commitment to the sum at a challenge,
+    /// 3. each prover message is fed on its own, followed by the challenge of its round.
+    ///
+    /// Returns `Error::OtherError` if the polynomial does not sum to the output of `sum_oracle`.
+    pub fn prove_with_sum_oracle<R: FeedableRNG<Error = crate::Error>>(
+        fs_rng: &mut R,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        sum_oracle: impl FnOnce(&mut R) -> F,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::check_provable(polynomial)?;
+        let polynomial_info = polynomial.info();
+        fs_rng.feed(&polynomial_info)?;
+        let claimed_sum = sum_oracle(fs_rng);
+        let prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut proof = Vec::with_capacity(polynomial_info.num_variables);
+        let prover_state =
+            Self::emit_prover_rounds(fs_rng, &polynomial_info, prover_state, None, false, |msg| {
+                proof.push(msg);
+                Ok(())
+            })?;
+        if Self::try_extract_sum(&proof)? != claimed_sum {
+            return Err(crate::Error::OtherError(
+                "the polynomial does not sum to the output of the sum oracle".into(),
+            ));
+        }
+        Ok((proof, prover_state))
+    }
+
+    /// run the prover as `prove` does, but only measure the proof instead of producing it
+    ///
+    /// All the arithmetic of the prover and the transcript is done, so that `elapsed` is the time
+    /// `prove` would take, but every prover message is dropped as soon as it is measured. Returns the
+    /// same errors as `prove`.
+    #[cfg(feature = "std")]
+    pub fn prove_dry_run<C: Copy + Into<F>>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+    ) -> Result<ProveProfile, crate::Error> {
+        Self::check_provable(polynomial)?;
+        let start = std::time::Instant::now();
+        let mut fs_rng = Blake2s512Rng::setup();
+        let prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut rounds = 0;
+        let mut round_message_len = 0;
+        Self::emit_prover_rounds(
+            &mut fs_rng,
+            &polynomial.info(),
+            prover_state,
+            None,
+            true,
+            |msg| {
+                rounds += 1;
+                round_message_len = msg.evaluations.len();
+                Ok(())
+            },
+        )?;
+        Ok(ProveProfile {
+            rounds,
+            round_message_len,
+            elapsed: start.elapsed(),
+        })
+    }
 
+    /// same as `prove_as_subprotocol`, but the challenge of each round is the next one of
+    /// `challenges` instead of being derived from a transcript
+    ///
+    /// Nothing binds the prover messages to the challenges here, so the caller is responsible for
+    /// the soundness of the challenges, as documented by `BatchChallengeSource`. `challenges` must
+    /// produce `num_variables` challenges. The proof is checked by `verify_with_challenges` with a
+    /// source producing the same challenges.
+    pub fn prove_with_challenges<C: Copy + Into<F>>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+        challenges: &mut impl ChallengeSource<F>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::check_provable(polynomial)?;
         let mut prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut proof = Vec::with_capacity(polynomial.num_variables);
         let mut verifier_msg = None;
-        let mut prover_msgs = Vec::with_capacity(polynomial.num_variables);
         for _ in 0..polynomial.num_variables {
-            let prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
-            fs_rng.feed(&prover_msg)?;
-            prover_msgs.push(prover_msg);
-            verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
+            proof.push(IPForMLSumcheck::prove_round(
+                &mut prover_state,
+                &verifier_msg,
+            ));
+            verifier_msg = Some(VerifierMsg {
+                randomness: challenges.next(),
+            });
         }
-        prover_state
-            .randomness
-            .push(verifier_msg.unwrap().randomness);
-        Ok((prover_msgs, prover_state))
+        if let Some(msg) = verifier_msg {
+            prover_state.randomness.push(msg.randomness);
+        }
+        Ok((proof, prover_state))
+    }
+
+    /// same as `prove_as_subprotocol`, but writes each prover message to `writer` as soon as it is
+    /// produced instead of returning the proof
+    ///
+    /// The messages are written in their compressed canonical encoding, one after the other, which is
+    /// not the encoding of a `Proof`. They are read back by `verify_from_reader`.
+    pub fn prove_to_writer(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        mut writer: impl Write,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<ProverState<F>, crate::Error> {
+        Self::check_provable(polynomial)?;
+        let prover_state = IPForMLSumcheck::prover_init(polynomial);
+        Self::emit_prover_rounds(
+            fs_rng,
+            &polynomial.info(),
+            prover_state,
+            None,
+            true,
+            |msg| Ok(msg.serialize_compressed(&mut writer)?),
+        )
+    }
+
+    /// verify the prover messages written by `prove_to_writer`, reading one message per round
+    ///
+    /// Returns `Error::SerializationError` if `reader` ends before `num_variables` messages are read.
+    pub fn verify_from_reader(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        mut reader: impl Read,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let mut verifier_state = IPForMLSumcheck::verifier_init(polynomial_info);
+        for i in 0..polynomial_info.num_variables {
+            let prover_msg = ProverMsg::deserialize_compressed(&mut reader)?;
+            feed_round_message(fs_rng, polynomial_info, &prover_msg, i == 0)?;
+            let _verifier_msg =
+                IPForMLSumcheck::verify_round(prover_msg, &mut verifier_state, fs_rng);
+        }
+        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+    }
+
+    /// generate proof of the sum of a list of products whose multiplicands may have fewer variables
+    ///
+    /// `products` holds a coefficient and the indices into `multiplicands` of each product. A
+    /// multiplicand with `k < num_variables` variables depends on the first `k` variables only, and is
+    /// constant in the others. It is folded over its own variables without being expanded. The proof
+    /// is the proof of the explicitly broadcast polynomial, and is checked by `verify`.
+    pub fn prove_broadcast(
+        num_variables: usize,
+        products: &[(F, Vec<usize>)],
+        multiplicands: Vec<DenseMultilinearExtension<F>>,
+    ) -> Result<Proof<F>, crate::Error> {
+        if num_variables == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        if multiplicands.iter().any(|m| m.num_vars > num_variables) {
+            return Err(crate::Error::OtherError(
+                "a multiplicand has more variables than the polynomial".into(),
+            ));
+        }
+        let oracles = multiplicands
+            .into_iter()
+            .map(|table| BroadcastMLE::new(table, num_variables))
+            .collect();
+        Self::prove_with_oracles(products, oracles)
+    }
+
+    /// generate proof of the sum of a list of products whose multiplicands are given by oracles
+    ///
+    /// `products` holds a coefficient and the indices into `oracles` of each product. Multiplicands of
+    /// different kinds, such as `DenseMultilinearExtension` and `Reflected`, are mixed by boxing them
+    /// as `Box<dyn MultilinearOracle<F>>`. An empty product is the constant polynomial of its
+    /// coefficient, as for `ListOfProductsOfPolynomials::add_product`. Returns an error if a
+    /// product refers to a missing oracle, if there is no oracle, or if the oracles do not all have
+    /// the same number of variables. The proof is checked by `verify`.
+    pub fn prove_with_oracles<O: MultilinearOracle<F>>(
+        products: &[(F, Vec<usize>)],
+        oracles: Vec<O>,
+    ) -> Result<Proof<F>, crate::Error> {
+        if products.is_empty() {
+            return Err(crate::Error::EmptyPolynomial);
+        }
+        let prover_state =
+            IPForMLSumcheck::try_prover_init_from_oracles(products.to_vec(), oracles)?;
+        let polynomial_info = PolynomialInfo {
+            max_multiplicands: prover_state.max_multiplicands,
+            num_variables: prover_state.num_vars,
+        };
+        let mut fs_rng = Blake2s512Rng::setup();
+        Self::run_prover_rounds(&mut fs_rng, &polynomial_info, prover_state, None).map(|r| r.0)
+    }
+
+    /// generate proof of the sum of the multilinear extension whose evaluations on the hypercube are
+    /// given by `oracle`
+    ///
+    /// `oracle` receives a point of `{0,1}^num_vars`, with entry `i` the value of variable `i`. It is
+    /// queried once per point, so `2^num_vars` times, to build the table of the first round, which is
+    /// then folded as in `prove_as_subprotocol`. The proof is for a polynomial of `max_multiplicands`
+    /// multiplicands, so that it can be checked by `verify` with the same polynomial info. Returns
+    /// `Error::ConstantPolynomial` if `num_vars` is zero, and an error if `num_vars` does not fit in a
+    /// table or `max_multiplicands` is zero, before `oracle` is queried.
+    pub fn prove_from_oracle(
+        num_vars: usize,
+        max_multiplicands: usize,
+        oracle: impl Fn(&[bool]) -> F,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        if num_vars == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        if num_vars >= usize::BITS as usize {
+            return Err(crate::Error::OtherError(format!(
+                "a table cannot have {} variables",
+                num_vars
+            )));
+        }
+        if max_multiplicands == 0 {
+            return Err(crate::Error::OtherError(
+                "the polynomial needs at least one multiplicand".into(),
+            ));
+        }
+        let mut point = vec![false; num_vars];
+        let evaluations = (0..1usize << num_vars)
+            .map(|index| {
+                for (i, bit) in point.iter_mut().enumerate() {
+                    *bit = index & (1 << i) != 0;
+                }
+                oracle(&point)
+            })
+            .collect();
+        let mut polynomial = ListOfProductsOfPolynomials::new(num_vars);
+        polynomial.add_product(
+            iter::once(Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                num_vars,
+                evaluations,
+            ))),
+            F::one(),
+        );
+        // the round polynomials have degree one, and are sent with `max_multiplicands + 1` evaluations
+        polynomial.raise_degree(max_multiplicands);
+        Self::prove_as_subprotocol(fs_rng, &polynomial)
+    }
+
+    /// same as `prove_as_subprotocol`, but binds the variables in the order given by `perm`
+    ///
+    /// Round `j` fixes variable `perm[j]` of `polynomial`, so this is the proof of
+    /// `polynomial.permute_variables(perm)`. Returns an error if `perm` is not a permutation of
+    /// `0..num_variables`. The proof is checked by `verify_with_permutation` with the same `perm`.
+    pub fn prove_with_permutation(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        perm: &[usize],
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        Self::check_permutation(perm, polynomial.num_variables)?;
+        // `permute_variables` indexes the tables of the polynomial
+        Self::check_provable(polynomial)?;
+        Self::prove_as_subprotocol(fs_rng, &polynomial.permute_variables(perm))
+    }
+
+    /// Returns an error if `perm` is not a permutation of `0..num_variables`.
+    fn check_permutation(perm: &[usize], num_variables: usize) -> Result<(), crate::Error> {
+        let mut seen = vec![false; num_variables];
+        for &i in perm {
+            if i >= num_variables || seen[i] {
+                return Err(crate::Error::OtherError(
+                    "not a permutation of the variables".into(),
+                ));
+            }
+            seen[i] = true;
+        }
+        if perm.len() != num_variables {
+            return Err(crate::Error::OtherError(
+                "not a permutation of the variables".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// generate proof that the polynomial sums to zero over the boolean hypercube
+    ///
+    /// Same as `prove`, but returns an error instead of a proof if the sum is not zero. The proof is
+    /// checked by `verify_zero`, which does not need the claimed sum.
+    pub fn prove_zero(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<Proof<F>, crate::Error> {
+        let proof = Self::prove(polynomial)?;
+        if !Self::try_extract_sum(&proof)?.is_zero() {
+            return Err(crate::Error::OtherError(
+                "the polynomial does not sum to zero".into(),
+            ));
+        }
+        Ok(proof)
+    }
+
+    /// generate proofs of several polynomials in sequence under one shared transcript
+    ///
+    /// Each polynomial is proved with `prove_as_subprotocol` on the same `Blake2s512Rng`, in the order
+    /// of `polynomials`, which binds the proofs together. They are checked by `verify_sequence`.
+    pub fn prove_sequence(
+        polynomials: &[&ListOfProductsOfPolynomials<F>],
+    ) -> Result<Vec<Proof<F>>, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        polynomials
+            .iter()
+            .map(|polynomial| Self::prove_as_subprotocol(&mut fs_rng, polynomial).map(|r| r.0))
+            .collect()
+    }
+
+    /// generate proofs of independent polynomials on `pool`
+    ///
+    /// Every proof is the same as the one of `prove` for that polynomial, with its own fresh
+    /// transcript, so a failing instance does not affect the others. The prover tables of all
+    /// instances are copied upfront on the calling thread, since the polynomials cannot be shared
+    /// across threads; the rounds then run on `pool`. The results are in the same order as
+    /// `polynomials`.
+    #[cfg(feature = "parallel")]
+    pub fn prove_many(
+        polynomials: &[ListOfProductsOfPolynomials<F>],
+        pool: &rayon::ThreadPool,
+    ) -> Vec<Result<Proof<F>, crate::Error>> {
+        let jobs: Vec<_> = polynomials
+            .iter()
+            .map(|polynomial| {
+                Self::check_provable(polynomial)
+                    .map(|()| (polynomial.info(), IPForMLSumcheck::prover_init(polynomial)))
+            })
+            .collect();
+        pool.install(|| {
+            jobs.into_par_iter()
+                .map(|job| {
+                    let (polynomial_info, prover_state) = job?;
+                    let mut fs_rng = Blake2s512Rng::setup();
+                    Self::run_prover_rounds(&mut fs_rng, &polynomial_info, prover_state, None)
+                        .map(|r| r.0)
+                })
+                .collect()
+        })
     }
 
     /// This function extends `prove_as_subprotocol` for use with two different lists of polynomials of different degrees
     /// Let polynomial_0 be the higher dimension polynomial.
+    ///
+    /// The two proofs share one transcript: every challenge depends on the messages of both proofs.
+    /// Neither proof can be checked by `verify_as_subprotocol` on its own, because a standalone
+    /// verifier would derive different challenges. Use `multi_degree_verify_separately` to check
+    /// each proof against its own sum.
     pub fn multi_degree_prove_as_subprotocol(
         fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
         polynomial_0: &ListOfProductsOfPolynomials<F>,
         polynomial_1: &ListOfProductsOfPolynomials<F>,
     ) -> Result<((Proof<F>, Proof<F>), (ProverState<F>, ProverState<F>)), crate::Error> {
-        assert!(polynomial_0.num_variables > polynomial_1.num_variables);
-        fs_rng.feed(&polynomial_0.info())?;
-        fs_rng.feed(&polynomial_1.info())?;
+        if polynomial_0.num_variables <= polynomial_1.num_variables {
+            return Err(crate::Error::OtherError(
+                "polynomial_0 should have more variables than polynomial_1".into(),
+            ));
+        }
+        Self::check_provable(polynomial_1)?;
+        Self::check_provable(polynomial_0)?;
+        let polynomial_info = (polynomial_0.info(), polynomial_1.info());
 
         let mut prover_0_state = IPForMLSumcheck::prover_init(polynomial_0);
         let mut prover_1_state = IPForMLSumcheck::prover_init(polynomial_1);
         let mut verifier_msg = None;
         let mut prover_0_msgs = Vec::with_capacity(polynomial_0.num_variables);
         let mut prover_1_msgs = Vec::with_capacity(polynomial_1.num_variables);
-        for _ in 0..polynomial_1.num_variables {
+        for round in 0..polynomial_1.num_variables {
             let prover_0_msg = IPForMLSumcheck::prove_round(&mut prover_0_state, &verifier_msg);
             let prover_1_msg = IPForMLSumcheck::prove_round(&mut prover_1_state, &verifier_msg);
-            fs_rng.feed(&prover_0_msg)?;
-            fs_rng.feed(&prover_1_msg)?;
+            if round == 0 {
+                fs_rng.feed_batch(&[
+                    &polynomial_info.0,
+                    &polynomial_info.1,
+                    &prover_0_msg,
+                    &prover_1_msg,
+                ])?;
+            } else {
+                fs_rng.feed_batch(&[&prover_0_msg, &prover_1_msg])?;
+            }
             prover_0_msgs.push(prover_0_msg);
             prover_1_msgs.push(prover_1_msg);
             verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
         }
-        prover_1_state
-            .randomness
-            .push(verifier_msg.clone().unwrap().randomness);
+        if let Some(msg) = &verifier_msg {
+            prover_1_state.randomness.push(msg.randomness);
+        }
         for _ in polynomial_1.num_variables..polynomial_0.num_variables {
             let prover_msg = IPForMLSumcheck::prove_round(&mut prover_0_state, &verifier_msg);
             fs_rng.feed(&prover_msg)?;
             prover_0_msgs.push(prover_msg);
             verifier_msg = Some(IPForMLSumcheck::sample_round(fs_rng));
         }
-        prover_0_state
-            .randomness
-            .push(verifier_msg.unwrap().randomness);
+        if let Some(msg) = verifier_msg {
+            prover_0_state.randomness.push(msg.randomness);
+        }
         Ok((
             (prover_0_msgs, prover_1_msgs),
             (prover_0_state, prover_1_state),
         ))
     }
 
-    /// verify the claimed sum using the proof
-    pub fn verify(
-        polynomial_info: &PolynomialInfo,
-        claimed_sum: F,
+    /// generate one proof for the random linear combination of several constraints
+    ///
+    /// The info and sum of every polynomial are fed to the transcript, then a challenge `rho` is
+    /// sampled and $\sum_x\sum_k\rho^k C_k(x)$ is proved with `prove_as_subprotocol`. All
+    /// polynomials must have the same number of variables. Returns the proof and `rho`; the sums
+    /// fed to the transcript are the ones checked by `verify_rlc`.
+    pub fn prove_rlc(
+        polynomials: &[&ListOfProductsOfPolynomials<F>],
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(Proof<F>, F), crate::Error> {
+        let nv = match polynomials.first() {
+            Some(polynomial) => polynomial.num_variables,
+            None => return Err(crate::Error::EmptyPolynomial),
+        };
+        if polynomials.iter().any(|p| p.num_variables != nv) {
+            return Err(crate::Error::OtherError(
+                "polynomials have different number of variables".into(),
+            ));
+        }
+        if nv == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        for polynomial in polynomials {
+            Self::check_provable(*polynomial)?;
+            // summed from the shared tables, without copying them into a prover state
+            let sum = polynomial
+                .products
+                .iter()
+                .map(|(coefficient, product)| {
+                    *coefficient
+                        * (0..1 << nv)
+                            .map(|x| {
+                                product
+                                    .iter()
+                                    .map(|&i| polynomial.flattened_ml_extensions[i].evaluations[x])
+                                    .product::<F>()
+                            })
+                            .sum::<F>()
+                })
+                .sum::<F>();
+            fs_rng.feed_batch(&[&polynomial.info(), &sum])?;
+        }
+        let rho = F::rand(fs_rng);
+        let mut combined = ListOfProductsOfPolynomials::new(nv);
+        let mut power = F::one();
+        for polynomial in polynomials {
+            let mut scaled = (*polynomial).clone();
+            for (coefficient, _) in scaled.products.iter_mut() {
+                *coefficient *= power;
+            }
+            combined.merge(&scaled);
+            power *= rho;
+        }
+        let (proof, _) = Self::prove_as_subprotocol(fs_rng, &combined)?;
+        Ok((proof, rho))
+    }
+
+    /// prove a polynomial whose coefficients are powers of a challenge `rho` drawn from the
+    /// transcript
+    ///
+    /// The info of the polynomial and `polynomial.sums_by_power()` are fed to a new transcript, then
+    /// `rho` is sampled and `polynomial.materialize(rho)` is proved with `prove_as_subprotocol`, so
+    /// the coefficients are only computed once `rho` is known. Returns the proof, the sums by power
+    /// and `rho`; the sums are the ones checked by `verify_self_scaling`. Returns
+    /// `Error::DegreeOverflow` if a power is larger than `ListOfProductsOfPolynomials::MAX_POWER`.
+    pub fn prove_self_scaling(
+        polynomial: &ListOfProductsOfPolynomials<F, ChallengePower>,
+    ) -> Result<(Proof<F>, Vec<F>, F), crate::Error> {
+        Self::check_provable(polynomial)?;
+        let mut fs_rng = Blake2s512Rng::setup();
+        let sums = polynomial.sums_by_power()?;
+        fs_rng.feed(&polynomial.info())?;
+        fs_rng.feed(&sums)?;
+        let rho = F::rand(&mut fs_rng);
+        let (proof, _) = Self::prove_as_subprotocol(&mut fs_rng, &polynomial.materialize(rho))?;
+        Ok((proof, sums, rho))
+    }
+
+    /// sum `f` over its first `k` variables, and prove the result
+    ///
+    /// Returns `g(y) = \sum_{x\in\{0,1\}^k} f(x, y)`, where `x` are the first `k` variables of
+    /// `f` and `y` the remaining ones, in the same order as in `f`: `g.evaluations[j]` is the sum of
+    /// `f.evaluations[j * 2^k..(j + 1) * 2^k]`. `g` is fed to the transcript, which gives a random
+    /// `r_y`, and the proof is a sumcheck over `x` of `f(x, r_y)` with the sum `g(r_y)`. It is checked
+    /// by `verify_partial_sum`.
+    pub fn prove_partial_sum(
+        f: &DenseMultilinearExtension<F>,
+        k: usize,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(DenseMultilinearExtension<F>, Proof<F>), crate::Error> {
+        if k == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        if k > f.num_vars {
+            return Err(crate::Error::OtherError(
+                "cannot sum over more variables than the polynomial has".into(),
+            ));
+        }
+        let block = 1 << k;
+        let g = DenseMultilinearExtension::from_evaluations_vec(
+            f.num_vars - k,
+            f.evaluations
+                .chunks(block)
+                .map(|chunk| chunk.iter().sum())
+                .collect(),
+        );
+        let r_y = Self::sample_partial_sum_point(&g, fs_rng)?;
+        // f(x, r_y) = \sum_y eq(y, r_y) f(x, y)
+        let eq_y = build_eq_x_r(&r_y);
+        let mut fixed = vec![F::zero(); block];
+        for (chunk, eq) in f.evaluations.chunks(block).zip(&eq_y.evaluations) {
+            for (fixed_x, f_x) in fixed.iter_mut().zip(chunk) {
+                *fixed_x += *eq * f_x;
+            }
+        }
+        let mut polynomial = ListOfProductsOfPolynomials::new(k);
+        polynomial.add_product(
+            iter::once(Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                k, fixed,
+            ))),
+            F::one(),
+        );
+        let (proof, _) = Self::prove_as_subprotocol(fs_rng, &polynomial)?;
+        Ok((g, proof))
+    }
+
+    /// verify that `g` is the sum of a polynomial over its first `k` variables
+    ///
+    /// Returns a subclaim on the summed polynomial `f` itself: `f(subclaim.point)` must equal
+    /// `subclaim.expected_evaluation`, where the point has the `k` summed variables first.
+    pub fn verify_partial_sum(
+        g: &DenseMultilinearExtension<F>,
+        k: usize,
         proof: &Proof<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
     ) -> Result<SubClaim<F>, crate::Error> {
-        let mut fs_rng = Blake2s512Rng::setup();
-        Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)
+        let r_y = Self::sample_partial_sum_point(g, fs_rng)?;
+        let claimed_sum = g
+            .evaluate(&r_y)
+            .ok_or_else(|| crate::Error::OtherError("cannot evaluate the partial sum".into()))?;
+        let polynomial_info = PolynomialInfo {
+            max_multiplicands: 1,
+            num_variables: k,
+        };
+        let mut subclaim =
+            Self::verify_as_subprotocol(fs_rng, &polynomial_info, claimed_sum, proof)?;
+        subclaim.point.extend(r_y);
+        Ok(subclaim)
     }
 
-    /// This function does the same thing as `prove`, but it uses a `FeedableRNG` as the transcript/to generate the
-    /// verifier challenges. This allows this sumcheck to be used as a part of a larger protocol.
-    pub fn multi_degree_verify_as_subprotocol(
+    /// feed the partial sum `g` and sample a point for its variables
+    fn sample_partial_sum_point(
+        g: &DenseMultilinearExtension<F>,
         fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
-        polynomial_info: (&PolynomialInfo, &PolynomialInfo),
+    ) -> Result<Vec<F>, crate::Error> {
+        fs_rng.feed(&g.evaluations)?;
+        let mut r_y = Vec::with_capacity(g.num_vars);
+        for _ in 0..g.num_vars {
+            r_y.push(F::rand(fs_rng));
+        }
+        Ok(r_y)
+    }
+
+    /// generate proof of $\sum_x f(x)^{power}$ over the hypercube, for `power` a power of two
+    ///
+    /// For `power = 2^k`, let $f_0 = f$ and let $f_{j+1}$ be the multilinear extension of the table of
+    /// $f_j$ squared pointwise, so that $f_j$ agrees with $f^{2^j}$ on the hypercube. Instead of one
+    /// sumcheck of degree `2^k`, the sum is reduced through `k` squaring layers of degree at most 3:
+    /// * the first layer proves $\sum_x f_{k-1}(x)^2$ and sends $v = f_{k-1}(r)$ at its point $r$,
+    /// * the layer of $f_j$ proves $v = f_{j+1}(r) = \sum_y eq(r, y) f_j(y)^2$, and sends the
+    ///   evaluation of $f_j$ at its own point.
+    ///
+    /// This ends in a claim about $f_0 = f$ at the last point. All layers share `fs_rng`, which also
+    /// absorbs each sent evaluation. For `power = 1` the proof is a single sumcheck of `f`. The proof
+    /// is checked by `verify_power_sum`. Returns an error if `power` is not a power of two.
+    pub fn prove_power_sum(
+        f: &DenseMultilinearExtension<F>,
+        power: usize,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<PowerSumProof<F>, crate::Error> {
+        let num_layers = Self::num_squaring_layers(power)?;
+        if num_layers == 0 {
+            let mut polynomial = ListOfProductsOfPolynomials::new(f.num_vars);
+            polynomial.add_product(iter::once(Rc::new(f.clone())), F::one());
+            let (proof, _) = Self::prove_as_subprotocol(fs_rng, &polynomial)?;
+            return Ok(PowerSumProof {
+                proofs: vec![proof],
+                evaluations: Vec::new(),
+            });
+        }
+        let mut tables = Vec::with_capacity(num_layers);
+        tables.push(f.clone());
+        for j in 1..num_layers {
+            let squared = tables[j - 1]
+                .evaluations
+                .iter()
+                .map(|e| e.square())
+                .collect();
+            tables.push(DenseMultilinearExtension::from_evaluations_vec(
+                f.num_vars, squared,
+            ));
+        }
+        let mut proofs = Vec::with_capacity(num_layers);
+        let mut evaluations = Vec::with_capacity(num_layers);
+        let mut point: Option<Vec<F>> = None;
+        for table in tables.into_iter().rev() {
+            let table = Rc::new(table);
+            let mut layer = ListOfProductsOfPolynomials::new(f.num_vars);
+            match &point {
+                None => layer.add_product(vec![table.clone(), table.clone()], F::one()),
+                Some(r) => layer.add_product(
+                    vec![Rc::new(build_eq_x_r(r)), table.clone(), table.clone()],
+                    F::one(),
+                ),
+            }
+            let (proof, prover_state) = Self::prove_as_subprotocol(fs_rng, &layer)?;
+            let r = prover_state.randomness;
+            let v = table
+                .evaluate(&r)
+                .expect("the point has one entry per variable");
+            fs_rng.feed(&v)?;
+            proofs.push(proof);
+            evaluations.push(v);
+            point = Some(r);
+        }
+        Ok(PowerSumProof {
+            proofs,
+            evaluations,
+        })
+    }
+
+    /// verify a proof generated by `prove_power_sum` that $\sum_x f(x)^{power}$ is `claimed_sum`,
+    /// for `f` in `num_variables` variables
+    ///
+    /// Each layer is verified with `verify_as_subprotocol`, and its subclaim is checked against the
+    /// evaluation sent by the prover, which becomes the claimed sum of the next layer. Returns the
+    /// subclaim that `f` evaluates to `expected_evaluation` at `point`, which is left to the caller.
+    pub fn verify_power_sum(
+        num_variables: usize,
+        power: usize,
         claimed_sum: F,
-        proofs: (&Proof<F>, &Proof<F>),
+        proof: &PowerSumProof<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
     ) -> Result<SubClaim<F>, crate::Error> {
-        fs_rng.feed(polynomial_info.0)?;
-        fs_rng.feed(polynomial_info.1)?;
-        let mut verifiers_state = (
-            IPForMLSumcheck::verifier_init(polynomial_info.0),
-            IPForMLSumcheck::verifier_init(polynomial_info.1),
-        );
-        for i in 0..polynomial_info.1.num_variables {
-            let provers_msg = (
-                proofs.0.get(i).expect("proof is incomplete"),
-                proofs.1.get(i).expect("proof is incomplete"),
-            );
-            fs_rng.feed(provers_msg.0)?;
-            fs_rng.feed(provers_msg.1)?;
-            let _verifier_msgs = IPForMLSumcheck::multi_degree_verify_round(
-                ((*provers_msg.0).clone(), (*provers_msg.1).clone()),
-                &mut verifiers_state,
+        let num_layers = Self::num_squaring_layers(power)?;
+        if num_layers == 0 {
+            if proof.proofs.len() != 1 || !proof.evaluations.is_empty() {
+                return Err(crate::Error::Reject(Some("wrong number of layers".into())));
+            }
+            let polynomial_info = PolynomialInfo {
+                max_multiplicands: 1,
+                num_variables,
+            };
+            return Self::verify_as_subprotocol(
                 fs_rng,
+                &polynomial_info,
+                claimed_sum,
+                &proof.proofs[0],
             );
         }
-        for i in polynomial_info.1.num_variables..polynomial_info.0.num_variables {
-            let prover_msg = proofs.0.get(i).expect("proof is incomplete");
-            fs_rng.feed(prover_msg)?;
-            let _verifier_msg_0 = IPForMLSumcheck::verify_round(
-                (*prover_msg).clone(),
-                &mut verifiers_state.0,
-                fs_rng,
-            );
+        if proof.proofs.len() != num_layers || proof.evaluations.len() != num_layers {
+            return Err(crate::Error::Reject(Some("wrong number of layers".into())));
         }
-        IPForMLSumcheck::multi_degree_check_and_generate_subclaim(verifiers_state, claimed_sum)
+        let mut claim = claimed_sum;
+        let mut point: Option<Vec<F>> = None;
+        for (i, (layer_proof, &v)) in proof.proofs.iter().zip(&proof.evaluations).enumerate() {
+            let polynomial_info = PolynomialInfo {
+                max_multiplicands: if point.is_none() { 2 } else { 3 },
+                num_variables,
+            };
+            let subclaim =
+                Self::verify_as_subprotocol(fs_rng, &polynomial_info, claim, layer_proof)?;
+            let eq = point
+                .as_ref()
+                .map_or(F::one(), |r| eq_eval(r, &subclaim.point));
+            if eq * v.square() != subclaim.expected_evaluation {
+                return Err(crate::Error::Reject(Some(format!(
+                    "the evaluation of layer {} does not match its subclaim",
+                    i
+                ))));
+            }
+            fs_rng.feed(&v)?;
+            claim = v;
+            point = Some(subclaim.point);
+        }
+        Ok(SubClaim {
+            point: point.expect("there is at least one layer"),
+            expected_evaluation: claim,
+        })
     }
 
-    /// This function does the same thing as `prove`, but it uses a `FeedableRNG` as the transcript/to generate the
-    /// verifier challenges. This allows this sumcheck to be used as a part of a larger protocol.
-    pub fn verify_as_subprotocol(
-        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
-        polynomial_info: &PolynomialInfo,
-        claimed_sum: F,
-        proof: &Proof<F>,
-    ) -> Result<SubClaim<F>, crate::Error> {
-        fs_rng.feed(polynomial_info)?;
-        let mut verifier_state = IPForMLSumcheck::verifier_init(polynomial_info);
-        for i in 0..polynomial_info.num_variables {
-            let prover_msg = proof.get(i).expect("proof is incomplete");
-            fs_rng.feed(prover_msg)?;
-            let _verifier_msg =
-                IPForMLSumcheck::verify_round((*prover_msg).clone(), &mut verifier_state, fs_rng);
+    /// Returns `log2(power)`, or an error if `power` is not a power of two.
+    fn num_squaring_layers(power: usize) -> Result<usize, crate::Error> {
+        if !power.is_power_of_two() {
+            return Err(crate::Error::OtherError(
+                "the power must be a power of two".into(),
+            ));
         }
+        Ok(power.trailing_zeros() as usize)
+    }
 
-        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+    /// prove the sum of `f` over the points of the hypercube where the selector `s` is 1
+    ///
+    /// Returns two proofs sharing `fs_rng`: a sumcheck of $\sum_x s(x) f(x)$, then an
+    /// `EqualityCheck` that `s^2 = s` on the hypercube, i.e. that `s` is boolean. Both are checked by
+    /// `verify_masked_sum`.
+    pub fn prove_masked_sum(
+        f: &DenseMultilinearExtension<F>,
+        s: &DenseMultilinearExtension<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(Proof<F>, Proof<F>), crate::Error> {
+        let masked = Self::masked_polynomial(f, s)?;
+        let (square, selector) = Self::booleanity_polynomials(s);
+        let (sum_proof, _) = Self::prove_as_subprotocol(fs_rng, &masked)?;
+        let booleanity_proof = EqualityCheck::prove(&square, &selector, fs_rng)?;
+        Ok((sum_proof, booleanity_proof))
+    }
+
+    /// verify that `claimed_sum` is the sum of `f` where the boolean selector `s` is 1
+    ///
+    /// `proofs` is the output of `prove_masked_sum`. Like `EqualityCheck::verify`, the verifier
+    /// needs (oracle) access to `f` and `s` for the final checks.
+    pub fn verify_masked_sum(
+        f: &DenseMultilinearExtension<F>,
+        s: &DenseMultilinearExtension<F>,
+        claimed_sum: F,
+        proofs: (&Proof<F>, &Proof<F>),
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(), crate::Error> {
+        let masked = Self::masked_polynomial(f, s)?;
+        let (square, selector) = Self::booleanity_polynomials(s);
+        let subclaim = Self::verify_as_subprotocol(fs_rng, &masked.info(), claimed_sum, proofs.0)?;
+        if masked.evaluate(&subclaim.point) != subclaim.expected_evaluation {
+            return Err(crate::Error::Reject(Some(
+                "s(r) * f(r) is not consistent with the subclaim".into(),
+            )));
+        }
+        EqualityCheck::verify(&square, &selector, proofs.1, fs_rng).map_err(|_| {
+            crate::Error::Reject(Some("the selector is not boolean on the hypercube".into()))
+        })
+    }
+
+    /// returns the polynomial `s * f` of a masked sum
+    fn masked_polynomial(
+        f: &DenseMultilinearExtension<F>,
+        s: &DenseMultilinearExtension<F>,
+    ) -> Result<ListOfProductsOfPolynomials<F>, crate::Error> {
+        if f.num_vars != s.num_vars {
+            return Err(crate::Error::OtherError(
+                "polynomials have different number of variables".into(),
+            ));
+        }
+        let mut masked = ListOfProductsOfPolynomials::new(f.num_vars);
+        masked.add_product(vec![Rc::new(s.clone()), Rc::new(f.clone())], F::one());
+        Ok(masked)
+    }
+
+    /// returns the polynomials `s^2` and `s`, which agree on the hypercube iff `s` is boolean there
+    fn booleanity_polynomials(
+        s: &DenseMultilinearExtension<F>,
+    ) -> (
+        ListOfProductsOfPolynomials<F>,
+        ListOfProductsOfPolynomials<F>,
+    ) {
+        let s = Rc::new(s.clone());
+        let mut square = ListOfProductsOfPolynomials::new(s.num_vars);
+        square.add_product(vec![s.clone(), s.clone()], F::one());
+        let mut selector = ListOfProductsOfPolynomials::new(s.num_vars);
+        selector.add_product(iter::once(s), F::one());
+        (square, selector)
+    }
+
+    /// generate proof of the sum of a tensor product over {0,1}^`num_vars`, without materializing it
+    ///
+    /// The proof is the same as the one of `prove_as_subprotocol` for the polynomial with the single
+    /// multiplicand `tensor.materialize()`, and is verified against `tensor.info()`. Memory use is
+    /// `2^n + 2^m` instead of `2^(n+m)`. Returns the proof and the challenges.
+    pub fn prove_tensor_product(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        tensor: &TensorProduct<F>,
+    ) -> Result<(Proof<F>, Vec<F>), crate::Error> {
+        let nv = tensor.num_variables();
+        if nv == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        let polynomial_info = tensor.info();
+        let mut f = tensor.f.clone();
+        let mut g = tensor.g.clone();
+        // sum of `g` over its hypercube, which scales every message of the rounds of `f`
+        let g_sum: F = g.evaluations.iter().sum();
+        let half_sums = |table: &DenseMultilinearExtension<F>| {
+            table
+                .evaluations
+                .chunks(2)
+                .fold((F::zero(), F::zero()), |(p0, p1), pair| {
+                    (p0 + pair[0], p1 + pair[1])
+                })
+        };
+        let mut prover_msgs = Vec::with_capacity(nv);
+        let mut challenges = Vec::with_capacity(nv);
+        for round in 0..nv {
+            let evaluations = if round < tensor.f.num_vars {
+                let (p0, p1) = half_sums(&f);
+                vec![p0 * g_sum, p1 * g_sum]
+            } else {
+                // `f` is completely fixed at this point
+                let f_value = f.evaluations[0];
+                let (p0, p1) = half_sums(&g);
+                vec![f_value * p0, f_value * p1]
+            };
+            let prover_msg = ProverMsg { evaluations };
+            feed_round_message(fs_rng, &polynomial_info, &prover_msg, round == 0)?;
+            prover_msgs.push(prover_msg);
+            let r = IPForMLSumcheck::sample_round(fs_rng).randomness;
+            if round < tensor.f.num_vars {
+                f = f.fix_variables(&[r]);
+            } else {
+                g = g.fix_variables(&[r]);
+            }
+            challenges.push(r);
+        }
+        Ok((prover_msgs, challenges))
+    }
+
+    /// pad the proof with trivial rounds so that it has exactly `target_rounds` rounds
+    ///
+    /// Each padding round is a degree-0 message with a single zero evaluation. Padding rounds are never
+    /// fed to the transcript, so a padded proof has to be verified with `verify_padded`.
+    /// Returns `Error::WrongProofLength` if `proof` already has more than `target_rounds` rounds.
+    pub fn pad_to(proof: &Proof<F>, target_rounds: usize) -> Result<Proof<F>, crate::Error> {
+        if target_rounds < proof.len() {
+            return Err(crate::Error::WrongProofLength {
+                expected: target_rounds,
+                got: proof.len(),
+            });
+        }
+        let mut padded = proof.clone();
+        padded.resize(
+            target_rounds,
+            ProverMsg {
+                evaluations: vec![F::zero()],
+            },
+        );
+        Ok(padded)
+    }
+
+    /// verify the claimed sum using a proof padded by `pad_to`
+    ///
+    /// Only the first `polynomial_info.num_variables` rounds take part in the protocol, and every remaining
+    /// round must be exactly the padding message. Since the padding is fixed and never fed to the transcript,
+    /// a padded proof is accepted if and only if the unpadded proof is accepted: padding does not affect soundness.
+    /// Returns `Error::WrongProofLength` if `proof` has fewer rounds than the polynomial has variables.
+    pub fn verify_padded(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let num_rounds = polynomial_info.num_variables;
+        if proof.len() < num_rounds {
+            return Err(crate::Error::WrongProofLength {
+                expected: num_rounds,
+                got: proof.len(),
+            });
+        }
+        let (rounds, padding) = proof.split_at(num_rounds);
+        if padding
+            .iter()
+            .any(|msg| msg.evaluations.len() != 1 || !msg.evaluations[0].is_zero())
+        {
+            return Err(crate::Error::Reject(Some("invalid padding round".into())));
+        }
+        Self::verify(polynomial_info, claimed_sum, &rounds.to_vec())
+    }
+
+    /// generate proof of the sum of polynomial over the points of {0,1}^`num_vars` where variable
+    /// `var_index` is 0
+    ///
+    /// The proof is a standard proof for the polynomial restricted by
+    /// `ListOfProductsOfPolynomials::restrict_variable`, and is checked by `verify_even`.
+    pub fn prove_even(
+        var_index: usize,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<Proof<F>, crate::Error> {
+        Self::prove_restricted_variable(var_index, false, polynomial)
+    }
+
+    /// same as `prove_even`, for the points where variable `var_index` is 1
+    pub fn prove_odd(
+        var_index: usize,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<Proof<F>, crate::Error> {
+        Self::prove_restricted_variable(var_index, true, polynomial)
+    }
+
+    /// verify a proof generated by `prove_even`
+    ///
+    /// `polynomial_info` is the info of the unrestricted polynomial, and the point of the subclaim has
+    /// the fixed coordinate inserted at `var_index`, so it can be checked against that polynomial.
+    pub fn verify_even(
+        var_index: usize,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        Self::verify_restricted_variable(var_index, false, polynomial_info, claimed_sum, proof)
+    }
+
+    /// verify a proof generated by `prove_odd`. See `verify_even`.
+    pub fn verify_odd(
+        var_index: usize,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        Self::verify_restricted_variable(var_index, true, polynomial_info, claimed_sum, proof)
+    }
+
+    fn prove_restricted_variable(
+        var_index: usize,
+        bit: bool,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<Proof<F>, crate::Error> {
+        if var_index >= polynomial.num_variables {
+            return Err(crate::Error::OtherError(
+                "variable index out of range".into(),
+            ));
+        }
+        // `restrict_variable` indexes the tables of the polynomial
+        Self::check_provable(polynomial)?;
+        Self::prove(&polynomial.restrict_variable(var_index, bit))
+    }
+
+    fn verify_restricted_variable(
+        var_index: usize,
+        bit: bool,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if var_index >= polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(
+                "variable index out of range".into(),
+            )));
+        }
+        let restricted_info = PolynomialInfo {
+            max_multiplicands: polynomial_info.max_multiplicands,
+            num_variables: polynomial_info.num_variables - 1,
+        };
+        let mut subclaim = Self::verify(&restricted_info, claimed_sum, proof)?;
+        let fixed = if bit { F::one() } else { F::zero() };
+        subclaim.point.insert(var_index, fixed);
+        Ok(subclaim)
+    }
+
+    /// generate proof of the sum of polynomial over {0, `shift`}^`num_vars` instead of {0,1}^`num_vars`
+    ///
+    /// The sum over the scaled hypercube is reduced to the standard protocol for `Q(y) = P(shift * y)`
+    /// (see `ListOfProductsOfPolynomials::scale_domain`), which is again a list of products of multilinear
+    /// extensions. In terms of `P`, the round polynomial of round `i` is `g_i(X) = q_i(X / shift)`, so
+    /// checking `q_i(0) + q_i(1)` is the same as checking `g_i(0) + g_i(shift)`, and the challenges of
+    /// `P` are the challenges of `Q` multiplied by `shift`. This is why no change of the interpolation is needed.
+    ///
+    /// Only scaled boolean hypercubes are supported, and `shift` must be non-zero.
+    pub fn prove_over_domain(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        shift: F,
+    ) -> Result<(Proof<F>, ProverState<F>), crate::Error> {
+        if shift.is_zero() {
+            return Err(crate::Error::OtherError("shift must be non-zero".into()));
+        }
+        // `scale_domain` indexes the tables of the polynomial
+        Self::check_provable(polynomial)?;
+        fs_rng.feed(&shift)?;
+        Self::prove_as_subprotocol(fs_rng, &polynomial.scale_domain(shift))
+    }
+
+    /// verify the claimed sum of a proof generated by `prove_over_domain`
+    ///
+    /// The point of the returned subclaim is expressed in the coordinates of the original polynomial,
+    /// i.e. `polynomial.evaluate(&subclaim.point)` should equal `subclaim.expected_evaluation`.
+    pub fn verify_over_domain(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        shift: F,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if shift.is_zero() {
+            return Err(crate::Error::Reject(Some("shift must be non-zero".into())));
+        }
+        fs_rng.feed(&shift)?;
+        let mut subclaim =
+            Self::verify_as_subprotocol(fs_rng, polynomial_info, claimed_sum, proof)?;
+        subclaim.point.iter_mut().for_each(|r| *r *= shift);
+        Ok(subclaim)
+    }
+
+    /// verify the claimed sum using the proof
+    pub fn verify(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)
+    }
+
+    /// same as `verify`, but also returns the transcript after the last round, so that the caller
+    /// can keep using it
+    ///
+    /// The transcript is the one a prover using `prove_as_subprotocol` with a fresh
+    /// `Blake2s512Rng` is left with, so both sides can continue the same Fiat-Shamir chain.
+    pub fn verify_continuing(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, Blake2s512Rng), crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let subclaim =
+            Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)?;
+        Ok((subclaim, fs_rng))
+    }
+
+    /// same as `verify`, but also returns the witness of the verification: the prover message of
+    /// each round, in order, paired with the challenge sampled after it
+    ///
+    /// The challenges are the coordinates of `subclaim.point`. This is the private input of a
+    /// verifier running inside a circuit.
+    pub fn verify_collect_witness(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, Vec<(ProverMsg<F>, F)>), crate::Error> {
+        let subclaim = Self::verify(polynomial_info, claimed_sum, proof)?;
+        let witness = proof
+            .iter()
+            .cloned()
+            .zip(subclaim.point.iter().copied())
+            .collect();
+        Ok((subclaim, witness))
+    }
+
+    /// same as `verify`, but also returns a 32-byte digest squeezed from the transcript after the
+    /// last round
+    ///
+    /// The digest depends on the polynomial info, every prover message and every challenge, and is
+    /// the one returned by `prove_with_digest` for the same proof. It can be absorbed by the
+    /// transcript or commitment of a parent protocol.
+    pub fn verify_with_digest(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, [u8; 32]), crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let subclaim =
+            Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)?;
+        let mut digest = [0u8; 32];
+        fs_rng.fill_bytes(&mut digest);
+        Ok((subclaim, digest))
+    }
+
+    /// same as `verify`, but also returns a `VerificationCertificate` of the verification, which
+    /// `VerificationCertificate::check` can audit against the proof
+    pub fn verify_with_certificate(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, VerificationCertificate<F>), crate::Error> {
+        let (subclaim, transcript_digest) =
+            Self::verify_with_digest(polynomial_info, claimed_sum, proof)?;
+        let certificate = VerificationCertificate {
+            claimed_sum,
+            subclaim_point: subclaim.point.clone(),
+            expected_evaluation: subclaim.expected_evaluation,
+            transcript_digest,
+        };
+        Ok((subclaim, certificate))
+    }
+
+    /// verify a proof committed to by the Merkle root `root` of its round messages
+    ///
+    /// `openings[i]` is the message of round `i` with its path, as given by
+    /// `merkle::merkle_openings`. Every path is checked against `root` before any message is used,
+    /// then the messages are verified as by `verify`. Returns `Error::Reject` if a message does not
+    /// open to `root`.
+    pub fn verify_against_merkle_root(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        root: &[u8; 32],
+        openings: &[MerkleOpening<F>],
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if openings.len() != polynomial_info.num_variables {
+            return Err(crate::Error::WrongProofLength {
+                expected: polynomial_info.num_variables,
+                got: openings.len(),
+            });
+        }
+        if let Some(round) = (0..openings.len()).find(|&i| !openings[i].verify(i, root)) {
+            return Err(crate::Error::Reject(Some(format!(
+                "the message of round {} does not open to the root",
+                round
+            ))));
+        }
+        let proof: Proof<F> = openings.iter().map(|o| o.message.clone()).collect();
+        Self::verify(polynomial_info, claimed_sum, &proof)
+    }
+
+    /// same as `verify`, with the verifier computations configured by `options`
+    ///
+    /// `verify` is the same as this function with `VerifyOptions::default()`.
+    pub fn verify_with_options(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        options: &VerifyOptions,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let verifier_state = Self::run_verifier_rounds(&mut fs_rng, polynomial_info, proof)?;
+        IPForMLSumcheck::check_and_generate_subclaim_with_options(
+            verifier_state,
+            claimed_sum,
+            options,
+        )
+    }
+
+    /// same as `prove`, with the round polynomials of the proof in the form given by `encoding`
+    ///
+    /// The transcript absorbs each round polynomial in the form it is sent, so a proof in
+    /// coefficient form has other challenges than `prove` and is only checked by
+    /// `verify_with_encoding` with `ProofEncoding::Coefficients`.
+    pub fn prove_with_encoding(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        encoding: ProofEncoding,
+    ) -> Result<Proof<F>, crate::Error> {
+        if encoding == ProofEncoding::Evaluations {
+            return Self::prove(polynomial);
+        }
+        Self::check_provable(polynomial)?;
+        let polynomial_info = polynomial.info();
+        let mut fs_rng = Blake2s512Rng::setup();
+        let mut prover_state = IPForMLSumcheck::prover_init(polynomial);
+        let mut verifier_msg = None;
+        let mut proof = Vec::with_capacity(polynomial_info.num_variables);
+        for round in 0..polynomial_info.num_variables {
+            let msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+            let prover_msg = ProverMsg {
+                evaluations: evaluations_to_coefficients(&msg.evaluations),
+            };
+            feed_round_message(&mut fs_rng, &polynomial_info, &prover_msg, round == 0)?;
+            proof.push(prover_msg);
+            verifier_msg = Some(IPForMLSumcheck::sample_round(&mut fs_rng));
+        }
+        Ok(proof)
+    }
+
+    /// Returns `proof`, whose round polynomials are in the form `from`, with its round polynomials
+    /// in the form `to`.
+    ///
+    /// The challenges depend on the form of the round polynomials, so the converted proof does not
+    /// verify: this is meant to inspect a proof, not to re-encode it.
+    pub fn convert_encoding(proof: &Proof<F>, from: ProofEncoding, to: ProofEncoding) -> Proof<F> {
+        let convert: fn(&[F]) -> Vec<F> = match (from, to) {
+            (ProofEncoding::Evaluations, ProofEncoding::Coefficients) => {
+                evaluations_to_coefficients
+            }
+            (ProofEncoding::Coefficients, ProofEncoding::Evaluations) => {
+                coefficients_to_evaluations
+            }
+            _ => return proof.clone(),
+        };
+        proof
+            .iter()
+            .map(|msg| ProverMsg {
+                evaluations: convert(&msg.evaluations),
+            })
+            .collect()
+    }
+
+    /// verify a proof generated by `prove_with_encoding` with the same `encoding`
+    ///
+    /// In coefficient form, the coefficients are absorbed as sent, and each round polynomial is
+    /// evaluated at its challenge by Horner's rule, so that the verifier never interpolates.
+    pub fn verify_with_encoding(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        encoding: ProofEncoding,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if encoding == ProofEncoding::Evaluations {
+            return Self::verify(polynomial_info, claimed_sum, proof);
+        }
+        Self::check_proof_length(proof, polynomial_info.num_variables)?;
+        let mut fs_rng = Blake2s512Rng::setup();
+        let mut expected = claimed_sum;
+        let mut point = Vec::with_capacity(polynomial_info.num_variables);
+        for (i, msg) in proof.iter().enumerate() {
+            let coefficients = &msg.evaluations;
+            if coefficients.len() < 2 || coefficients.len() - 1 != polynomial_info.max_multiplicands
+            {
+                return Err(crate::Error::Reject(Some(format!(
+                    "incorrect number of coefficients at round {}",
+                    i
+                ))));
+            }
+            feed_round_message(&mut fs_rng, polynomial_info, msg, i == 0)?;
+            // P(0) is the constant term, and P(1) is the sum of the coefficients
+            let (p0, p1) = (coefficients[0], coefficients.iter().sum::<F>());
+            if p0 + p1 != expected {
+                return Err(round_check_failed(i, expected, p0, p1));
+            }
+            let r = IPForMLSumcheck::sample_round(&mut fs_rng).randomness;
+            expected = horner_evaluate(coefficients, r);
+            point.push(r);
+        }
+        Ok(SubClaim {
+            point,
+            expected_evaluation: expected,
+        })
+    }
+
+    /// verify that the polynomial sums to zero, using a proof generated by `prove_zero`
+    ///
+    /// Same as `verify` with a claimed sum of zero, except that the sum of the first round
+    /// polynomial is checked before any other work, and a nonzero sum is rejected with a specific
+    /// error.
+    pub fn verify_zero(
+        polynomial_info: &PolynomialInfo,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if let Some([p0, p1, ..]) = proof.first().map(|msg| msg.evaluations.as_slice()) {
+            if !(*p0 + p1).is_zero() {
+                return Err(crate::Error::Reject(Some(
+                    "round 0: the polynomial does not sum to zero".into(),
+                )));
+            }
+        }
+        Self::verify(polynomial_info, F::zero(), proof)
+    }
+
+    /// verify proofs generated by `prove_sequence`, in the same order, under one shared transcript
+    ///
+    /// The instances are verified one after the other with the same `Blake2s512Rng`, so the
+    /// challenges of an instance depend on all the instances before it. The order of
+    /// `info_and_proofs` must therefore be exactly the order used by the prover. Proofs generated
+    /// separately with fresh transcripts, e.g. by `prove`, are rejected: their challenges were not
+    /// derived from the shared transcript. Returns one subclaim per instance.
+    pub fn verify_sequence(
+        info_and_proofs: &[(PolynomialInfo, F, Proof<F>)],
+    ) -> Result<Vec<SubClaim<F>>, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        info_and_proofs
+            .iter()
+            .map(|(polynomial_info, claimed_sum, proof)| {
+                Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, *claimed_sum, proof)
+            })
+            .collect()
+    }
+
+    /// same as `verify`, but also rejects the proof if a round polynomial has degree above `cap`
+    ///
+    /// A round polynomial of degree `d` is sent as `d + 1` evaluations, so every message must have at
+    /// most `cap + 1` evaluations. This is checked before any verification work.
+    pub fn verify_with_degree_cap(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        cap: usize,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if let Some(round) = proof
+            .iter()
+            .position(|msg| msg.evaluations.len() > cap.saturating_add(1))
+        {
+            return Err(crate::Error::Reject(Some(format!(
+                "round polynomial {round} has degree above the cap {cap}"
+            ))));
+        }
+        Self::verify(polynomial_info, claimed_sum, proof)
+    }
+
+    /// the bytes fed to the transcript when verifying `proof`, in order
+    ///
+    /// This is the encoding of `polynomial_info`, followed by the encoding of each prover message,
+    /// exactly as passed to `FeedableRNG::feed`. The challenge of round `i` is sampled right after
+    /// the message of round `i` is fed.
+    pub fn transcript_bytes(polynomial_info: &PolynomialInfo, proof: &Proof<F>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        polynomial_info
+            .serialize_uncompressed(&mut bytes)
+            .expect("serializing to a vector does not fail");
+        for msg in proof {
+            msg.serialize_uncompressed(&mut bytes)
+                .expect("serializing to a vector does not fail");
+        }
+        bytes
+    }
+
+    /// verify a batch of independent proofs in parallel
+    ///
+    /// Each job `(polynomial_info, claimed_sum, proof)` is checked with `verify` on a worker thread.
+    /// Every job uses its own fresh transcript, so a rejected proof does not affect the result of
+    /// the others. The results are returned in the same order as `jobs`.
+    #[cfg(feature = "parallel")]
+    pub fn batch_verify_parallel(
+        jobs: &[(PolynomialInfo, F, &Proof<F>)],
+    ) -> Vec<Result<SubClaim<F>, crate::Error>> {
+        jobs.par_iter()
+            .map(|(polynomial_info, claimed_sum, proof)| {
+                Self::verify(polynomial_info, *claimed_sum, proof)
+            })
+            .collect()
+    }
+
+    /// This function does the same thing as `prove`, but it uses a `FeedableRNG` as the transcript/to generate the
+    /// verifier challenges. This allows this sumcheck to be used as a part of a larger protocol.
+    pub fn multi_degree_verify_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: (&PolynomialInfo, &PolynomialInfo),
+        claimed_sum: F,
+        proofs: (&Proof<F>, &Proof<F>),
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let verifiers_state =
+            Self::run_multi_degree_verifier_rounds(fs_rng, polynomial_info, proofs)?;
+        IPForMLSumcheck::multi_degree_check_and_generate_subclaim(verifiers_state, claimed_sum)
+    }
+
+    /// verify the two proofs produced by `multi_degree_prove_as_subprotocol` against their own sums
+    ///
+    /// The shared transcript is replayed with both proofs, and each proof is then checked as a
+    /// standalone sumcheck using the challenges of the shared transcript. The point of the second
+    /// subclaim is a prefix of the point of the first one.
+    pub fn multi_degree_verify_separately(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: (&PolynomialInfo, &PolynomialInfo),
+        claimed_sums: (F, F),
+        proofs: (&Proof<F>, &Proof<F>),
+    ) -> Result<(SubClaim<F>, SubClaim<F>), crate::Error> {
+        let verifiers_state =
+            Self::run_multi_degree_verifier_rounds(fs_rng, polynomial_info, proofs)?;
+        Ok((
+            IPForMLSumcheck::check_and_generate_subclaim(verifiers_state.0, claimed_sums.0)?,
+            IPForMLSumcheck::check_and_generate_subclaim(verifiers_state.1, claimed_sums.1)?,
+        ))
+    }
+
+    /// feed both polynomial infos and the interleaved prover messages to the transcript, and run the
+    /// verifier rounds of both proofs
+    fn run_multi_degree_verifier_rounds(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: (&PolynomialInfo, &PolynomialInfo),
+        proofs: (&Proof<F>, &Proof<F>),
+    ) -> Result<(VerifierState<F>, VerifierState<F>), crate::Error> {
+        let nv = (
+            polynomial_info.0.num_variables,
+            polynomial_info.1.num_variables,
+        );
+        if nv.1 == 0 || nv.0 <= nv.1 {
+            return Err(crate::Error::Reject(Some(
+                "the first polynomial should have more variables than the second one".into(),
+            )));
+        }
+        Self::check_proof_length(proofs.0, nv.0)?;
+        Self::check_proof_length(proofs.1, nv.1)?;
+        let mut verifiers_state = (
+            IPForMLSumcheck::verifier_init(polynomial_info.0),
+            IPForMLSumcheck::verifier_init(polynomial_info.1),
+        );
+        for (i, provers_msg) in proofs.0.iter().zip(proofs.1.iter()).take(nv.1).enumerate() {
+            if i == 0 {
+                fs_rng.feed_batch(&[
+                    polynomial_info.0,
+                    polynomial_info.1,
+                    provers_msg.0,
+                    provers_msg.1,
+                ])?;
+            } else {
+                fs_rng.feed_batch(&[provers_msg.0, provers_msg.1])?;
+            }
+            let _verifier_msgs = IPForMLSumcheck::multi_degree_verify_round(
+                ((*provers_msg.0).clone(), (*provers_msg.1).clone()),
+                &mut verifiers_state,
+                fs_rng,
+            );
+        }
+        for prover_msg in &proofs.0[nv.1..nv.0] {
+            fs_rng.feed(prover_msg)?;
+            let _verifier_msg_0 = IPForMLSumcheck::verify_round(
+                (*prover_msg).clone(),
+                &mut verifiers_state.0,
+                fs_rng,
+            );
+        }
+        Ok(verifiers_state)
+    }
+
+    /// This function does the same thing as `prove`, but it uses a `FeedableRNG` as the transcript/to generate the
+    /// verifier challenges. This allows this sumcheck to be used as a part of a larger protocol.
+    pub fn verify_as_subprotocol(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let verifier_state = Self::run_verifier_rounds(fs_rng, polynomial_info, proof)?;
+        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+    }
+
+    /// verify a proof generated by `prove_with_sum_oracle`, where the claimed sum is the output of
+    /// `sum_oracle`
+    ///
+    /// The polynomial info is fed to the transcript, then `sum_oracle` is called on the transcript,
+    /// and only then are the prover messages fed, each on its own. `sum_oracle` must use the
+    /// transcript as the one of the prover does.
+    pub fn verify_with_sum_oracle<R: FeedableRNG<Error = crate::Error>>(
+        fs_rng: &mut R,
+        polynomial_info: &PolynomialInfo,
+        sum_oracle: impl FnOnce(&mut R) -> F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        fs_rng.feed(polynomial_info)?;
+        let claimed_sum = sum_oracle(fs_rng);
+        let verifier_state = Self::run_verifier_rounds_with(fs_rng, polynomial_info, proof, false)?;
+        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+    }
+
+    /// verify a proof generated by `prove_with_challenges`, where the challenge of each round is the
+    /// next one of `challenges`
+    ///
+    /// `challenges` must produce `polynomial_info.num_variables` challenges.
+    pub fn verify_with_challenges(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        challenges: &mut impl ChallengeSource<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        Self::check_proof_length(proof, polynomial_info.num_variables)?;
+        let mut verifier_state = IPForMLSumcheck::verifier_init(polynomial_info);
+        for prover_msg in proof {
+            IPForMLSumcheck::verify_round_with_challenge(
+                prover_msg.clone(),
+                &mut verifier_state,
+                challenges.next(),
+            );
+        }
+        IPForMLSumcheck::check_and_generate_subclaim(verifier_state, claimed_sum)
+    }
+
+    /// check that the round polynomials of `proof` are consistent with `claimed_sum` when the
+    /// challenges are `point`, without any transcript
+    ///
+    /// Each round polynomial must satisfy `g(0) + g(1)` equal to the claimed sum for the first round,
+    /// and to the previous round polynomial at its challenge for the others. This separates the
+    /// algebraic consistency of a proof from the hash of its transcript: `point` is e.g. the point
+    /// of the subclaim of `verify`, and the same relations hold under any transcript deriving these
+    /// challenges. It is not a proof of the sum on its own, since a prover knowing `point` in advance
+    /// can satisfy it for any sum.
+    ///
+    /// Returns `Error::OtherError` if `point` does not have one challenge per variable.
+    pub fn verify_structural(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        point: &[F],
+    ) -> Result<(), crate::Error> {
+        if point.len() != polynomial_info.num_variables {
+            return Err(crate::Error::OtherError(format!(
+                "{} challenges for {} variables",
+                point.len(),
+                polynomial_info.num_variables
+            )));
+        }
+        let mut challenges = BatchChallengeSource::new(point.to_vec());
+        Self::verify_with_challenges(polynomial_info, claimed_sum, proof, &mut challenges)
+            .map(|_| ())
+    }
+
+    /// Returns the first `round` rounds of `proof` and the remaining ones.
+    ///
+    /// `Proof` is a type alias, so this is defined here. Returns `Error::WrongProofLength` if
+    /// `round > proof.len()`.
+    pub fn split_proof(
+        proof: &Proof<F>,
+        round: usize,
+    ) -> Result<(Proof<F>, Proof<F>), crate::Error> {
+        if round > proof.len() {
+            return Err(crate::Error::WrongProofLength {
+                expected: round,
+                got: proof.len(),
+            });
+        }
+        let (prefix, suffix) = proof.split_at(round);
+        Ok((prefix.to_vec(), suffix.to_vec()))
+    }
+
+    /// verify the first rounds of a proof generated by `prove_as_subprotocol`, given by `prefix`
+    ///
+    /// Each round is checked as soon as it is fed to `fs_rng`. The returned handoff, together with
+    /// `fs_rng`, lets `verify_suffix` check the remaining rounds, possibly in another party. Returns
+    /// `Error::Reject` if `prefix` has more rounds than the polynomial, and
+    /// `Error::RoundCheckFailed` if a round fails its check.
+    pub fn verify_prefix(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        prefix: &[ProverMsg<F>],
+    ) -> Result<VerifierHandoff<F>, crate::Error> {
+        let mut handoff = VerifierHandoff {
+            expected_sum: claimed_sum,
+            point: Vec::with_capacity(polynomial_info.num_variables),
+        };
+        Self::verify_rounds_eagerly(fs_rng, polynomial_info, prefix, &mut handoff)?;
+        Ok(handoff)
+    }
+
+    /// verify the rounds of a proof from `start_round` on, given by `proof_suffix`, after
+    /// `verify_prefix` checked the previous rounds and returned `handoff`
+    ///
+    /// `fs_rng` must be the transcript as left by `verify_prefix`. The subclaim is the one `verify`
+    /// would return for the whole proof. Returns `Error::Reject` if `start_round` is not the number
+    /// of rounds of `handoff`, if `proof_suffix` does not complete the proof, or if a round fails.
+    pub fn verify_suffix(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        handoff: VerifierHandoff<F>,
+        polynomial_info: &PolynomialInfo,
+        proof_suffix: &[ProverMsg<F>],
+        start_round: usize,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if handoff.point.len() != start_round {
+            return Err(crate::Error::Reject(Some(
+                "the handoff does not end at the start round".into(),
+            )));
+        }
+        if start_round + proof_suffix.len() != polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(
+                "the suffix does not complete the proof".into(),
+            )));
+        }
+        let mut handoff = handoff;
+        Self::verify_rounds_eagerly(fs_rng, polynomial_info, proof_suffix, &mut handoff)?;
+        Ok(SubClaim {
+            point: handoff.point,
+            expected_evaluation: handoff.expected_sum,
+        })
+    }
+
+    /// check each round of `proof` as it is fed, continuing from `handoff`
+    fn verify_rounds_eagerly(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        proof: &[ProverMsg<F>],
+        handoff: &mut VerifierHandoff<F>,
+    ) -> Result<(), crate::Error> {
+        if handoff.point.len() + proof.len() > polynomial_info.num_variables {
+            return Err(crate::Error::Reject(Some(
+                "proof has more rounds than the polynomial has variables".into(),
+            )));
+        }
+        for prover_msg in proof {
+            let round = handoff.point.len();
+            let evaluations = &prover_msg.evaluations;
+            check_num_evaluations(evaluations, polynomial_info.max_multiplicands, round)?;
+            feed_round_message(fs_rng, polynomial_info, prover_msg, round == 0)?;
+            if evaluations[0] + evaluations[1] != handoff.expected_sum {
+                return Err(round_check_failed(
+                    round,
+                    handoff.expected_sum,
+                    evaluations[0],
+                    evaluations[1],
+                ));
+            }
+            let r = IPForMLSumcheck::sample_round(fs_rng).randomness;
+            handoff.expected_sum = interpolate_uni_poly(evaluations, r);
+            handoff.point.push(r);
+        }
+        Ok(())
+    }
+
+    /// verify a proof generated by `prove_with_permutation` with the same `perm`
+    ///
+    /// The subclaim point is in the original indexing of the variables, so the subclaim is about the
+    /// polynomial before permutation. Returns an error if `perm` is not a permutation of
+    /// `0..num_variables`.
+    pub fn verify_with_permutation(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+        perm: &[usize],
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        Self::check_permutation(perm, polynomial_info.num_variables)?;
+        let subclaim = Self::verify_as_subprotocol(fs_rng, polynomial_info, claimed_sum, proof)?;
+        let mut point = vec![F::zero(); perm.len()];
+        for (&i, r) in perm.iter().zip(subclaim.point) {
+            point[i] = r;
+        }
+        Ok(SubClaim {
+            point,
+            expected_evaluation: subclaim.expected_evaluation,
+        })
+    }
+
+    /// verify a proof generated by `prove_rlc`
+    ///
+    /// `polynomial_infos[k]` and `claimed_sums[k]` describe constraint `k`. Returns the subclaim of
+    /// the combined polynomial, whose expected evaluation is $\sum_k\rho^k C_k(point)$, and `rho`.
+    pub fn verify_rlc(
+        polynomial_infos: &[PolynomialInfo],
+        claimed_sums: &[F],
+        proof: &Proof<F>,
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    ) -> Result<(SubClaim<F>, F), crate::Error> {
+        if polynomial_infos.len() != claimed_sums.len() {
+            return Err(crate::Error::OtherError(
+                "number of polynomial infos and claimed sums differ".into(),
+            ));
+        }
+        let nv = match polynomial_infos.first() {
+            Some(info) => info.num_variables,
+            None => return Err(crate::Error::EmptyPolynomial),
+        };
+        if polynomial_infos.iter().any(|info| info.num_variables != nv) {
+            return Err(crate::Error::Reject(Some(
+                "polynomials have different number of variables".into(),
+            )));
+        }
+        for (info, sum) in polynomial_infos.iter().zip(claimed_sums) {
+            fs_rng.feed_batch(&[info, sum])?;
+        }
+        let rho = F::rand(fs_rng);
+        let combined_info = PolynomialInfo {
+            max_multiplicands: polynomial_infos
+                .iter()
+                .map(|info| info.max_multiplicands)
+                .max()
+                .unwrap_or(0),
+            num_variables: nv,
+        };
+        let mut combined_sum = F::zero();
+        let mut power = F::one();
+        for sum in claimed_sums {
+            combined_sum += power * sum;
+            power *= rho;
+        }
+        let subclaim = Self::verify_as_subprotocol(fs_rng, &combined_info, combined_sum, proof)?;
+        Ok((subclaim, rho))
+    }
+
+    /// verify a proof generated by `prove_self_scaling`
+    ///
+    /// `sums[k]` is the sum of the products with the coefficient `ChallengePower(k)`. `rho` is
+    /// re-derived from the transcript, and the proof is verified against $\sum_k\rho^k sums_k$.
+    /// Returns the subclaim, whose expected evaluation is the one of the polynomial materialized at
+    /// `rho`, and `rho`.
+    pub fn verify_self_scaling(
+        polynomial_info: &PolynomialInfo,
+        sums: &[F],
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, F), crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        fs_rng.feed(polynomial_info)?;
+        fs_rng.feed(&sums.to_vec())?;
+        let rho = F::rand(&mut fs_rng);
+        let claimed_sum = sums
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, sum| acc * rho + sum);
+        let subclaim =
+            Self::verify_as_subprotocol(&mut fs_rng, polynomial_info, claimed_sum, proof)?;
+        Ok((subclaim, rho))
+    }
+
+    /// find which of `candidates` the proof validates against
+    ///
+    /// The first prover message fixes the claimed sum, so only the first candidate equal to
+    /// `extract_sum(proof)` is verified. Returns its index and the subclaim, or `None` if no
+    /// candidate matches.
+    pub fn verify_candidates(
+        polynomial_info: &PolynomialInfo,
+        candidates: &[F],
+        proof: &Proof<F>,
+    ) -> Result<Option<(usize, SubClaim<F>)>, crate::Error> {
+        Self::check_proof_length(proof, polynomial_info.num_variables)?;
+        let sum = match proof.first() {
+            Some(msg) if msg.evaluations.len() >= 2 => msg.evaluations[0] + msg.evaluations[1],
+            _ => return Err(crate::Error::Reject(Some("malformed first round".into()))),
+        };
+        match candidates.iter().position(|candidate| *candidate == sum) {
+            Some(i) => Ok(Some((i, Self::verify(polynomial_info, sum, proof)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// verify the claimed sum using the proof, and return the expected sum of every round
+    ///
+    /// The `i`-th element is `P_i(r_i)`, the prover message of round `i` evaluated at its challenge,
+    /// which is the sum expected at round `i + 1`. The last element is the expected evaluation of the
+    /// subclaim. The same checks as `verify` are performed.
+    pub fn verify_collect_expected(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<Vec<F>, crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let verifier_state = Self::run_verifier_rounds(&mut fs_rng, polynomial_info, proof)?;
+        IPForMLSumcheck::check_and_collect_expected(&verifier_state, claimed_sum, &mut ())
+    }
+
+    /// verify a proof generated by `prove_committed`
+    pub fn verify_committed<C: CanonicalSerialize>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        commitment: &C,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        fs_rng.feed(commitment)?;
+        Self::verify_as_subprotocol(fs_rng, polynomial_info, claimed_sum, proof)
+    }
+
+    /// same as `verify_as_subprotocol`, but only accepts the verifier side of a transcript
+    pub fn verify_with_transcript<R: FeedableRNG<Error = crate::Error>>(
+        transcript: &mut VerifierTranscript<R>,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        Self::verify_as_subprotocol(transcript.as_rng_mut(), polynomial_info, claimed_sum, proof)
+    }
+
+    /// verify the claimed sum using the proof, and count the field operations performed
+    ///
+    /// The counts cover the round checks and interpolations done in `check_and_generate_subclaim`;
+    /// operations performed by the transcript are not included.
+    #[cfg(feature = "stats")]
+    pub fn verify_counting(
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<F>,
+    ) -> Result<(SubClaim<F>, VerifyStats), crate::Error> {
+        let mut fs_rng = Blake2s512Rng::setup();
+        let verifier_state = Self::run_verifier_rounds(&mut fs_rng, polynomial_info, proof)?;
+        let mut stats = VerifyStats::default();
+        let subclaim = IPForMLSumcheck::check_and_generate_subclaim_with_counter(
+            verifier_state,
+            claimed_sum,
+            &mut stats,
+        )?;
+        Ok((subclaim, stats))
+    }
+
+    /// feed `polynomial_info` and every prover message to the transcript, and run the verifier rounds
+    fn run_verifier_rounds(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        proof: &Proof<F>,
+    ) -> Result<VerifierState<F>, crate::Error> {
+        Self::run_verifier_rounds_with(fs_rng, polynomial_info, proof, true)
+    }
+
+    /// same as `run_verifier_rounds`, but unless `absorb_info` is set, `polynomial_info` is assumed
+    /// to be already fed, and the first message is fed alone
+    fn run_verifier_rounds_with(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        proof: &Proof<F>,
+        absorb_info: bool,
+    ) -> Result<VerifierState<F>, crate::Error> {
+        Self::check_proof_length(proof, polynomial_info.num_variables)?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "verify",
+            num_variables = polynomial_info.num_variables,
+            max_multiplicands = polynomial_info.max_multiplicands
+        )
+        .entered();
+        let mut verifier_state = IPForMLSumcheck::verifier_init(polynomial_info);
+        for (i, prover_msg) in proof.iter().enumerate() {
+            #[cfg(feature = "tracing")]
+            let _round_span = tracing::trace_span!("verify_round", round = i).entered();
+            feed_round_message(fs_rng, polynomial_info, prover_msg, i == 0 && absorb_info)?;
+            let _verifier_msg =
+                IPForMLSumcheck::verify_round((*prover_msg).clone(), &mut verifier_state, fs_rng);
+        }
+        Ok(verifier_state)
+    }
+}
+
+impl<F: PrimeField> MLSumcheck<F> {
+    /// same as `prove_as_subprotocol`, but runs the protocol over the extension field `E` of `F`, so
+    /// that every challenge is an element of `E` made of `[E : F]` base field elements
+    ///
+    /// The soundness error of sumcheck is about `num_variables * max_multiplicands / |F|` when the
+    /// challenges are sampled from `F`. This is negligible for fields of 128 bits or more, but over
+    /// 64-bit fields such as Goldilocks it is only around `2^-55` for typical sizes, which is why
+    /// challenges should be sampled from an extension of degree 2 or more there. The proof and the
+    /// subclaim are over `E`, and the polynomial is lifted by
+    /// `ListOfProductsOfPolynomials::lift_to_extension`.
+    pub fn prove_with_wide_challenges<E: Field<BasePrimeField = F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<(Proof<E>, ProverState<E>), crate::Error> {
+        MLSumcheck::<E>::prove_as_subprotocol(fs_rng, &polynomial.lift_to_extension())
+    }
+
+    /// verify a proof generated by `prove_with_wide_challenges`
+    pub fn verify_with_wide_challenges<E: Field<BasePrimeField = F>>(
+        fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+        polynomial_info: &PolynomialInfo,
+        claimed_sum: F,
+        proof: &Proof<E>,
+    ) -> Result<SubClaim<E>, crate::Error> {
+        let mut elems = vec![F::zero(); E::extension_degree() as usize];
+        elems[0] = claimed_sum;
+        let claimed_sum =
+            E::from_base_prime_field_elems(&elems).expect("extension degree is correct");
+        MLSumcheck::<E>::verify_as_subprotocol(fs_rng, polynomial_info, claimed_sum, proof)
+    }
+
+    /// parse a proof whose evaluations are encoded as canonical little-endian integers
+    ///
+    /// Some toolchains encode field elements as plain integers rather than with arkworks' serialization.
+    /// The bytes are the concatenation of `num_variables` rounds of `max_multiplicands + 1` evaluations,
+    /// each taking `ceil(MODULUS_BIT_SIZE / 8)` bytes. Returns `Error::SerializationError` if the length
+    /// does not match `polynomial_info`, or if any element is not smaller than the modulus.
+    pub fn proof_from_plain_bytes(
+        bytes: &[u8],
+        polynomial_info: &PolynomialInfo,
+    ) -> Result<Proof<F>, crate::Error> {
+        let element_size = Self::plain_element_size();
+        let round_size = polynomial_info
+            .max_multiplicands
+            .checked_add(1)
+            .and_then(|num_evaluations| num_evaluations.checked_mul(element_size));
+        let proof_size =
+            round_size.and_then(|round_size| round_size.checked_mul(polynomial_info.num_variables));
+        let round_size = match (round_size, proof_size) {
+            (Some(round_size), Some(proof_size)) if proof_size == bytes.len() => round_size,
+            _ => return Err(crate::Error::SerializationError),
+        };
+        bytes
+            .chunks(round_size)
+            .map(|round| -> Result<ProverMsg<F>, crate::Error> {
+                let evaluations = round
+                    .chunks(element_size)
+                    .map(|chunk| {
+                        let element = F::from_le_bytes_mod_order(chunk);
+                        // reject non-canonical encodings, i.e. integers not smaller than the modulus
+                        let mut canonical = ark_ff::BigInteger::to_bytes_le(&element.into_bigint());
+                        canonical.resize(element_size, 0);
+                        if canonical != chunk {
+                            return Err(crate::Error::SerializationError);
+                        }
+                        Ok(element)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ProverMsg { evaluations })
+            })
+            .collect()
+    }
+
+    /// encode a proof in the format read by `proof_from_plain_bytes`
+    pub fn proof_to_plain_bytes(proof: &Proof<F>) -> Vec<u8> {
+        let element_size = Self::plain_element_size();
+        let mut bytes = Vec::new();
+        for msg in proof {
+            for evaluation in &msg.evaluations {
+                let mut canonical = ark_ff::BigInteger::to_bytes_le(&evaluation.into_bigint());
+                canonical.resize(element_size, 0);
+                bytes.extend_from_slice(&canonical);
+            }
+        }
+        bytes
+    }
+
+    /// number of bytes of a field element in the plain encoding
+    fn plain_element_size() -> usize {
+        (F::MODULUS_BIT_SIZE as usize + 7) / 8
     }
 }
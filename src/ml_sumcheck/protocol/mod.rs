@@ -3,6 +3,7 @@
 use ark_ff::Field;
 use ark_std::marker::PhantomData;
 
+pub mod oracle;
 pub mod prover;
 pub mod verifier;
 pub use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
@@ -0,0 +1,184 @@
+//! Abstraction over the storage of the multiplicands used by the prover.
+
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::boxed::Box;
+
+/// A multilinear polynomial given by its evaluations on the boolean hypercube.
+///
+/// The prover only reads the evaluations of its tables and fixes their first variable once per
+/// round, so any storage providing these two operations can back a `ProverState`. Index `i` of the
+/// hypercube has the value of variable `j` in bit `j`.
+pub trait MultilinearOracle<F: Field>: Send + Sync {
+    /// number of variables
+    fn num_vars(&self) -> usize;
+
+    /// evaluation at the point of the hypercube with index `index`
+    fn evaluate_on_hypercube(&self, index: usize) -> F;
+
+    /// fix the first variable to `r`, which removes one variable
+    fn fold(&mut self, r: F);
+}
+
+/// Boxed oracles, so that multiplicands of different kinds can back the same `ProverState`.
+impl<F: Field, O: MultilinearOracle<F> + ?Sized> MultilinearOracle<F> for Box<O> {
+    fn num_vars(&self) -> usize {
+        (**self).num_vars()
+    }
+
+    #[inline]
+    fn evaluate_on_hypercube(&self, index: usize) -> F {
+        (**self).evaluate_on_hypercube(index)
+    }
+
+    fn fold(&mut self, r: F) {
+        (**self).fold(r)
+    }
+}
+
+impl<F: Field> MultilinearOracle<F> for DenseMultilinearExtension<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    #[inline]
+    fn evaluate_on_hypercube(&self, index: usize) -> F {
+        self.evaluations[index]
+    }
+
+    /// Folds in place, without reallocating the evaluations.
+    fn fold(&mut self, r: F) {
+        let half = self.evaluations.len() / 2;
+        for b in 0..half {
+            let low = self.evaluations[b << 1];
+            let high = self.evaluations[(b << 1) + 1];
+            self.evaluations[b] = low + (high - low) * r;
+        }
+        self.evaluations.truncate(half);
+        self.num_vars -= 1;
+    }
+}
+
+/// A multilinear extension over the first variables only, broadcast to more variables.
+///
+/// The value does not depend on the variables beyond those of `table`. Folding fixes a variable of
+/// `table` while it has some, and otherwise only drops a variable, so the table is never expanded.
+#[derive(Clone)]
+pub struct BroadcastMLE<F: Field> {
+    table: DenseMultilinearExtension<F>,
+    num_vars: usize,
+}
+
+impl<F: Field> BroadcastMLE<F> {
+    /// Broadcast `table` to `num_vars` variables, which must be at least `table.num_vars`.
+    pub fn new(table: DenseMultilinearExtension<F>, num_vars: usize) -> Self {
+        assert!(
+            table.num_vars <= num_vars,
+            "the broadcast table has more variables than the polynomial"
+        );
+        Self { table, num_vars }
+    }
+}
+
+impl<F: Field> MultilinearOracle<F> for BroadcastMLE<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    #[inline]
+    fn evaluate_on_hypercube(&self, index: usize) -> F {
+        // the table has a power of two entries, indexed by its own variables in the low bits
+        self.table.evaluations[index & (self.table.evaluations.len() - 1)]
+    }
+
+    fn fold(&mut self, r: F) {
+        if self.table.num_vars > 0 {
+            self.table.fold(r);
+        }
+        self.num_vars -= 1;
+    }
+}
+
+/// The multilinear extension `g(1 - x)`, where `1 - x` flips every variable, given the table of `g`.
+///
+/// The value at index `i` of the hypercube is read at the complement of `i` in the table of `g`, and
+/// fixing a variable to `r` fixes the variable of `g` to `1 - r`, so the reflected table is never
+/// built.
+#[derive(Clone)]
+pub struct Reflected<F: Field> {
+    table: DenseMultilinearExtension<F>,
+}
+
+impl<F: Field> Reflected<F> {
+    /// Returns the reflection of `table`.
+    pub fn new(table: DenseMultilinearExtension<F>) -> Self {
+        Self { table }
+    }
+}
+
+impl<F: Field> MultilinearOracle<F> for Reflected<F> {
+    fn num_vars(&self) -> usize {
+        self.table.num_vars
+    }
+
+    #[inline]
+    fn evaluate_on_hypercube(&self, index: usize) -> F {
+        let mask = self.table.evaluations.len() - 1;
+        self.table.evaluations[!index & mask]
+    }
+
+    fn fold(&mut self, r: F) {
+        self.table.fold(F::one() - r);
+    }
+}
+
+/// The multilinear extension of `g(i) = f((i + shift) mod 2^num_vars)` on the hypercube, given the
+/// table of `f`, i.e. `f` on the cyclically rotated domain.
+///
+/// The rotated table is never built. Fixing the first variable halves an even `shift` and folds the
+/// table of `f` in place; an odd `shift` is absorbed into the first fold, which writes the folded
+/// rotation into a table of half the size.
+#[derive(Clone)]
+pub struct Shifted<F: Field> {
+    table: DenseMultilinearExtension<F>,
+    shift: usize,
+}
+
+impl<F: Field> Shifted<F> {
+    /// Returns the rotation of `table` by `shift` positions.
+    pub fn new(table: DenseMultilinearExtension<F>, shift: usize) -> Self {
+        let shift = shift & (table.evaluations.len() - 1);
+        Self { table, shift }
+    }
+}
+
+impl<F: Field> MultilinearOracle<F> for Shifted<F> {
+    fn num_vars(&self) -> usize {
+        self.table.num_vars
+    }
+
+    #[inline]
+    fn evaluate_on_hypercube(&self, index: usize) -> F {
+        let mask = self.table.evaluations.len() - 1;
+        self.table.evaluations[(index + self.shift) & mask]
+    }
+
+    fn fold(&mut self, r: F) {
+        if self.shift % 2 == 0 {
+            self.table.fold(r);
+            self.shift /= 2;
+            return;
+        }
+        let half = self.table.evaluations.len() / 2;
+        let evaluations = (0..half)
+            .map(|b| {
+                let low = self.evaluate_on_hypercube(b << 1);
+                let high = self.evaluate_on_hypercube((b << 1) + 1);
+                low + (high - low) * r
+            })
+            .collect();
+        self.table =
+            DenseMultilinearExtension::from_evaluations_vec(self.table.num_vars - 1, evaluations);
+        self.shift = 0;
+    }
+}
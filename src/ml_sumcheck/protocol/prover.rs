@@ -1,10 +1,16 @@
 //! Prover
 use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use crate::ml_sumcheck::protocol::oracle::MultilinearOracle;
+#[cfg(feature = "prover-self-check")]
+use crate::ml_sumcheck::protocol::verifier::interpolate_uni_poly;
 use crate::ml_sumcheck::protocol::verifier::VerifierMsg;
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
+use crate::ml_sumcheck::MLSumcheck;
 use ark_ff::Field;
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::borrow::Borrow;
+use ark_std::cmp::max;
 use ark_std::{cfg_iter_mut, vec::Vec};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -15,21 +21,178 @@ pub struct ProverMsg<F: Field> {
     /// evaluations on P(0), P(1), P(2), ...
     pub(crate) evaluations: Vec<F>,
 }
+
+/// Form of the round polynomials in a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofEncoding {
+    /// evaluations at `0, 1, ..., max_multiplicands`, as produced by `MLSumcheck::prove`
+    Evaluations,
+    /// monomial coefficients, constant term first
+    Coefficients,
+}
+
 /// Prover State
-pub struct ProverState<F: Field> {
+///
+/// The tables of the multiplicands are stored as `O`, which is `DenseMultilinearExtension` unless
+/// the prover is initialized by `IPForMLSumcheck::prover_init_from_oracles`.
+pub struct ProverState<F: Field, O: MultilinearOracle<F> = DenseMultilinearExtension<F>> {
     /// sampled randomness given by the verifier
     pub randomness: Vec<F>,
     /// Stores the list of products that is meant to be added together. Each multiplicand is represented by
     /// the index in flattened_ml_extensions
     pub list_of_products: Vec<(F, Vec<usize>)>,
     /// Stores a list of multilinear extensions in which `self.list_of_products` points to
-    pub flattened_ml_extensions: Vec<DenseMultilinearExtension<F>>,
+    pub flattened_ml_extensions: Vec<O>,
     /// Number of variables
     pub num_vars: usize,
     /// Max number of multiplicands in a product
     pub max_multiplicands: usize,
     /// The current round number
     pub round: usize,
+    /// scratch space reused across rounds by `prove_round_into`
+    scratch: Vec<F>,
+    /// evaluations of the previous prover message, used by the self-check
+    #[cfg(feature = "prover-self-check")]
+    last_evaluations: Vec<F>,
+}
+
+/// Prover State that reads the multilinear extensions of the polynomial by reference.
+///
+/// Unlike `ProverState`, no upfront copy of the polynomial is made: the first round is computed
+/// directly from the borrowed tables, and the first fold writes into an owned workspace of half the
+/// size. The polynomial needs to stay alive until the first verifier message is received.
+pub struct BorrowedProverState<'a, F: Field> {
+    polynomial: &'a ListOfProductsOfPolynomials<F>,
+    /// the owned state, created by the first fold
+    folded: Option<ProverState<F>>,
+    /// whether the first prover message has been sent
+    started: bool,
+}
+
+impl<'a, F: Field> BorrowedProverState<'a, F> {
+    /// Returns the owned prover state, or `None` if the first fold has not happened yet.
+    pub fn folded_state(&self) -> Option<&ProverState<F>> {
+        self.folded.as_ref()
+    }
+
+    /// Consumes the borrowing state, and returns the owned prover state.
+    ///
+    /// Panics if the first fold has not happened yet.
+    pub fn into_prover_state(self) -> ProverState<F> {
+        match self.try_into_prover_state() {
+            Ok(state) => state,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// same as `into_prover_state`, but returns `Error::OtherError` instead of panicking if the
+    /// first fold has not happened yet
+    pub fn try_into_prover_state(self) -> Result<ProverState<F>, crate::Error> {
+        self.folded
+            .ok_or_else(|| crate::Error::OtherError("the first fold has not happened yet".into()))
+    }
+}
+
+impl<F: Field> IPForMLSumcheck<F> {
+    /// initialize a prover that borrows `polynomial` instead of copying its multilinear extensions
+    ///
+    /// This avoids holding the polynomial and a full copy of it at the same time, which roughly
+    /// halves peak memory of a single-use proof. Use `prove_round_borrowed` to run the rounds.
+    ///
+    /// Panics with the error of `try_prover_init_borrowed` if the polynomial cannot be proved.
+    pub fn prover_init_borrowed(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> BorrowedProverState<'_, F> {
+        match Self::try_prover_init_borrowed(polynomial) {
+            Ok(state) => state,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// same as `prover_init_borrowed`, but returns the error of `MLSumcheck::prove` instead of
+    /// panicking if the polynomial cannot be proved
+    pub fn try_prover_init_borrowed(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+    ) -> Result<BorrowedProverState<'_, F>, crate::Error> {
+        MLSumcheck::check_provable(polynomial)?;
+        Ok(BorrowedProverState {
+            polynomial,
+            folded: None,
+            started: false,
+        })
+    }
+
+    /// same as `prove_round`, for a prover initialized by `prover_init_borrowed`
+    ///
+    /// Panics with the error of `try_prove_round_borrowed` if the state machine is misused.
+    pub fn prove_round_borrowed(
+        prover_state: &mut BorrowedProverState<'_, F>,
+        v_msg: &Option<VerifierMsg<F>>,
+    ) -> ProverMsg<F> {
+        match Self::try_prove_round_borrowed(prover_state, v_msg) {
+            Ok(msg) => msg,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// same as `prove_round_borrowed`, but returns an error instead of panicking, as
+    /// `try_prove_round` does
+    pub fn try_prove_round_borrowed(
+        prover_state: &mut BorrowedProverState<'_, F>,
+        v_msg: &Option<VerifierMsg<F>>,
+    ) -> Result<ProverMsg<F>, crate::Error> {
+        if let Some(state) = prover_state.folded.as_mut() {
+            return Self::try_prove_round(state, v_msg);
+        }
+        let polynomial = prover_state.polynomial;
+        let tables: Vec<&DenseMultilinearExtension<F>> = polynomial
+            .flattened_ml_extensions
+            .iter()
+            .map(|x| x.as_ref())
+            .collect();
+        match (v_msg, prover_state.started) {
+            (None, false) => {
+                prover_state.started = true;
+                Ok(round_message::<F, DenseMultilinearExtension<F>, _>(
+                    &polynomial.products,
+                    &tables,
+                    polynomial.num_variables,
+                    polynomial.max_multiplicands,
+                    1,
+                ))
+            }
+            (Some(_), true) if polynomial.num_variables == 1 => Err(crate::Error::ProtocolFinished),
+            (Some(msg), true) => {
+                // fold the borrowed tables into the owned workspace
+                let r = msg.randomness;
+                let flattened_ml_extensions = ark_std::cfg_iter!(tables)
+                    .map(|multiplicand| multiplicand.fix_variables(&[r]))
+                    .collect();
+                let mut randomness = Vec::with_capacity(polynomial.num_variables);
+                randomness.push(r);
+                let mut state = ProverState {
+                    randomness,
+                    list_of_products: polynomial.products.clone(),
+                    flattened_ml_extensions,
+                    num_vars: polynomial.num_variables,
+                    max_multiplicands: polynomial.max_multiplicands,
+                    round: 2,
+                    scratch: Vec::new(),
+                    #[cfg(feature = "prover-self-check")]
+                    last_evaluations: Vec::new(),
+                };
+                let prover_msg = state.round_message(2);
+                prover_state.folded = Some(state);
+                Ok(prover_msg)
+            }
+            (Some(_), false) => Err(crate::Error::OtherError(
+                "the first round has no verifier message".into(),
+            )),
+            (None, true) => Err(crate::Error::OtherError(
+                "the verifier message is missing".into(),
+            )),
+        }
+    }
 }
 
 impl<F: Field> IPForMLSumcheck<F> {
@@ -46,35 +209,185 @@ impl<F: Field> IPForMLSumcheck<F> {
     ///
     /// $$\sum_{i=0}^{n}C_i\cdot\prod_{j=0}^{m_i}P_{ij}$$
     ///
-    pub fn prover_init(polynomial: &ListOfProductsOfPolynomials<F>) -> ProverState<F> {
-        if polynomial.num_variables == 0 {
-            panic!("Attempt to prove a constant.")
-        }
-
+    /// The coefficients of `polynomial` are converted into `F` here, once per product.
+    pub fn prover_init<C: Copy + Into<F>>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+    ) -> ProverState<F> {
         // create a deep copy of all unique MLExtensions
         let flattened_ml_extensions = polynomial
             .flattened_ml_extensions
             .iter()
             .map(|x| x.as_ref().clone())
             .collect();
+        Self::prover_init_with_tables(polynomial, flattened_ml_extensions)
+    }
+
+    /// initialize the prover with `flattened_ml_extensions`, a copy of the tables of `polynomial`
+    fn prover_init_with_tables<C: Copy + Into<F>>(
+        polynomial: &ListOfProductsOfPolynomials<F, C>,
+        flattened_ml_extensions: Vec<DenseMultilinearExtension<F>>,
+    ) -> ProverState<F> {
+        if polynomial.num_variables == 0 {
+            panic!("Attempt to prove a constant.")
+        }
 
         ProverState {
             randomness: Vec::with_capacity(polynomial.num_variables),
-            list_of_products: polynomial.products.clone(),
+            list_of_products: polynomial
+                .products
+                .iter()
+                .map(|(coefficient, product)| ((*coefficient).into(), product.clone()))
+                .collect(),
             flattened_ml_extensions,
             num_vars: polynomial.num_variables,
             max_multiplicands: polynomial.max_multiplicands,
             round: 0,
+            scratch: Vec::new(),
+            #[cfg(feature = "prover-self-check")]
+            last_evaluations: Vec::new(),
+        }
+    }
+
+    /// initialize the prover with multiplicands stored as `oracles`
+    ///
+    /// `list_of_products` has the same meaning as `ListOfProductsOfPolynomials::products`: each
+    /// product is a coefficient and the indices of its multiplicands in `oracles`, which must all
+    /// have the same number of variables. The proof is the same as for a `ListOfProductsOfPolynomials`
+    /// with these products and the evaluations of `oracles` as its multilinear extensions.
+    ///
+    /// Panics with the error of `try_prover_init_from_oracles` if the products or oracles are
+    /// malformed.
+    pub fn prover_init_from_oracles<O: MultilinearOracle<F>>(
+        list_of_products: Vec<(F, Vec<usize>)>,
+        oracles: Vec<O>,
+    ) -> ProverState<F, O> {
+        match Self::try_prover_init_from_oracles(list_of_products, oracles) {
+            Ok(state) => state,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// same as `prover_init_from_oracles`, but returns an error instead of panicking
+    ///
+    /// Returns `Error::OtherError` if there is no oracle, if the oracles do not all have the same
+    /// number of variables, or if a product refers to a missing oracle, and
+    /// `Error::ConstantPolynomial` if the oracles have no variable.
+    pub fn try_prover_init_from_oracles<O: MultilinearOracle<F>>(
+        list_of_products: Vec<(F, Vec<usize>)>,
+        oracles: Vec<O>,
+    ) -> Result<ProverState<F, O>, crate::Error> {
+        let num_vars = match oracles.first() {
+            Some(oracle) => oracle.num_vars(),
+            None => {
+                return Err(crate::Error::OtherError(
+                    "there is no oracle to give the number of variables".into(),
+                ))
+            }
+        };
+        if oracles.iter().any(|oracle| oracle.num_vars() != num_vars) {
+            return Err(crate::Error::OtherError(
+                "multiplicands have different numbers of variables".into(),
+            ));
         }
+        if num_vars == 0 {
+            return Err(crate::Error::ConstantPolynomial);
+        }
+        if list_of_products
+            .iter()
+            .any(|(_, product)| product.iter().any(|&i| i >= oracles.len()))
+        {
+            return Err(crate::Error::OtherError(
+                "a product refers to a missing multiplicand".into(),
+            ));
+        }
+        // an empty product is a constant, sent with degree 1 as by `add_product`
+        let max_multiplicands = list_of_products
+            .iter()
+            .map(|(_, product)| max(product.len(), 1))
+            .max()
+            .unwrap_or(0);
+        Ok(ProverState {
+            randomness: Vec::with_capacity(num_vars),
+            list_of_products,
+            flattened_ml_extensions: oracles,
+            num_vars,
+            max_multiplicands,
+            round: 0,
+            scratch: Vec::new(),
+            #[cfg(feature = "prover-self-check")]
+            last_evaluations: Vec::new(),
+        })
     }
 
     /// receive message from verifier, generate prover message, and proceed to next round
     ///
     /// Main algorithm used is from section 3.2 of [XZZPS19](https://eprint.iacr.org/2019/317.pdf#subsection.3.2).
-    pub fn prove_round(
-        prover_state: &mut ProverState<F>,
+    ///
+    /// With the `prover-self-check` feature, each message is checked against a brute-force sum of the
+    /// current tables, which costs O(2^n) per round.
+    pub fn prove_round<O: MultilinearOracle<F>>(
+        prover_state: &mut ProverState<F, O>,
         v_msg: &Option<VerifierMsg<F>>,
     ) -> ProverMsg<F> {
+        Self::advance_round(prover_state, v_msg);
+        let prover_msg = prover_state.round_message(prover_state.round);
+        #[cfg(feature = "prover-self-check")]
+        prover_state.self_check(&prover_msg.evaluations);
+        prover_msg
+    }
+
+    /// same as `prove_round`, but returns an error instead of panicking when the state machine is
+    /// misused
+    ///
+    /// Returns `Error::ProtocolFinished` once all `num_vars` rounds are done, and
+    /// `Error::OtherError` if `v_msg` is missing or unexpected. The state is left unchanged on error.
+    pub fn try_prove_round<O: MultilinearOracle<F>>(
+        prover_state: &mut ProverState<F, O>,
+        v_msg: &Option<VerifierMsg<F>>,
+    ) -> Result<ProverMsg<F>, crate::Error> {
+        if prover_state.round >= prover_state.num_vars {
+            return Err(crate::Error::ProtocolFinished);
+        }
+        match (prover_state.round, v_msg) {
+            (0, Some(_)) => Err(crate::Error::OtherError(
+                "the first round has no verifier message".into(),
+            )),
+            (round, None) if round > 0 => Err(crate::Error::OtherError(
+                "the verifier message is missing".into(),
+            )),
+            _ => Ok(Self::prove_round(prover_state, v_msg)),
+        }
+    }
+
+    /// same as `prove_round`, but writes the evaluations of the prover message into `buf`
+    ///
+    /// `buf` and the scratch space of `prover_state` are reused across rounds, so that without the
+    /// `parallel` feature no allocation is made per round once they have reached their final size.
+    /// With `parallel`, each rayon task still allocates its own partial sums.
+    pub fn prove_round_into<O: MultilinearOracle<F>>(
+        prover_state: &mut ProverState<F, O>,
+        v_msg: &Option<VerifierMsg<F>>,
+        buf: &mut Vec<F>,
+    ) {
+        Self::advance_round(prover_state, v_msg);
+        round_message_into::<F, O, _>(
+            &prover_state.list_of_products,
+            &prover_state.flattened_ml_extensions,
+            prover_state.num_vars,
+            prover_state.max_multiplicands,
+            prover_state.round,
+            buf,
+            &mut prover_state.scratch,
+        );
+        #[cfg(feature = "prover-self-check")]
+        prover_state.self_check(buf);
+    }
+
+    /// receive message from verifier, fix the next variable, and move to the next round
+    fn advance_round<O: MultilinearOracle<F>>(
+        prover_state: &mut ProverState<F, O>,
+        v_msg: &Option<VerifierMsg<F>>,
+    ) {
         if let Some(msg) = v_msg {
             if prover_state.round == 0 {
                 panic!("first round should be prover first.");
@@ -84,9 +397,8 @@ impl<F: Field> IPForMLSumcheck<F> {
             // fix argument
             let i = prover_state.round;
             let r = prover_state.randomness[i - 1];
-            cfg_iter_mut!(prover_state.flattened_ml_extensions).for_each(|multiplicand| {
-                *multiplicand = multiplicand.fix_variables(&[r]);
-            });
+            cfg_iter_mut!(prover_state.flattened_ml_extensions)
+                .for_each(|multiplicand| multiplicand.fold(r));
         } else if prover_state.round > 0 {
             panic!("verifier message is empty");
         }
@@ -96,59 +408,357 @@ impl<F: Field> IPForMLSumcheck<F> {
         if prover_state.round > prover_state.num_vars {
             panic!("Prover is not active");
         }
+    }
+}
 
-        let i = prover_state.round;
-        let nv = prover_state.num_vars;
-        let degree = prover_state.max_multiplicands; // the degree of univariate polynomial sent by prover at this round
-
-        #[cfg(not(feature = "parallel"))]
-        let zeros = (vec![F::zero(); degree + 1], vec![F::zero(); degree + 1]);
-        #[cfg(feature = "parallel")]
-        let zeros = || (vec![F::zero(); degree + 1], vec![F::zero(); degree + 1]);
-
-        // generate sum
-        let fold_result = ark_std::cfg_into_iter!(0..1 << (nv - i), 1 << 10).fold(
-            zeros,
-            |(mut products_sum, mut product), b| {
-                // In effect, this fold is essentially doing simply:
-                // for b in 0..1 << (nv - i) {
-                for (coefficient, products) in &prover_state.list_of_products {
-                    product.fill(*coefficient);
-                    for &jth_product in products {
-                        let table = &prover_state.flattened_ml_extensions[jth_product];
-                        let mut start = table[b << 1];
-                        let step = table[(b << 1) + 1] - start;
-                        for p in product.iter_mut() {
-                            *p *= start;
-                            start += step;
-                        }
-                    }
-                    for t in 0..degree + 1 {
-                        products_sum[t] += product[t];
-                    }
-                }
-                (products_sum, product)
-            },
+/// Buffers reused across proofs by `MLSumcheck::prove_in_workspace`.
+///
+/// After the first proof, proving polynomials of the same shape does not allocate prover tables
+/// anymore: they are cleared and refilled instead. A workspace is meant to be owned by a single
+/// thread; use one workspace per thread to prove in parallel.
+pub struct ProverWorkspace<F: Field> {
+    /// evaluation tables of the multiplicands
+    tables: Vec<Vec<F>>,
+    /// scratch space of the round messages
+    scratch: Vec<F>,
+}
+
+impl<F: Field> ProverWorkspace<F> {
+    /// Returns an empty workspace.
+    pub fn new() -> Self {
+        Self {
+            tables: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl<F: Field> Default for ProverWorkspace<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> IPForMLSumcheck<F> {
+    /// same as `prover_init`, but copies the multiplicands into the buffers of `workspace`
+    ///
+    /// The buffers are given back by `ProverState::release_into`.
+    pub fn prover_init_in_workspace(
+        polynomial: &ListOfProductsOfPolynomials<F>,
+        workspace: &mut ProverWorkspace<F>,
+    ) -> ProverState<F> {
+        let mut prover_state = Self::prover_init_with_tables(
+            polynomial,
+            polynomial
+                .flattened_ml_extensions
+                .iter()
+                .map(|x| {
+                    let mut table = workspace.tables.pop().unwrap_or_default();
+                    table.clear();
+                    table.extend_from_slice(&x.evaluations);
+                    DenseMultilinearExtension::from_evaluations_vec(x.num_vars, table)
+                })
+                .collect(),
         );
+        prover_state.scratch = ark_std::mem::take(&mut workspace.scratch);
+        prover_state
+    }
+}
 
-        #[cfg(not(feature = "parallel"))]
-        let products_sum = fold_result.0;
-
-        // When rayon is used, the `fold` operation results in a iterator of `Vec<F>` rather than a single `Vec<F>`. In this case, we simply need to sum them.
-        #[cfg(feature = "parallel")]
-        let products_sum = fold_result.map(|scratch| scratch.0).reduce(
-            || vec![F::zero(); degree + 1],
-            |mut overall_products_sum, sublist_sum| {
-                overall_products_sum
-                    .iter_mut()
-                    .zip(sublist_sum.iter())
-                    .for_each(|(f, s)| *f += s);
-                overall_products_sum
-            },
+impl<F: Field> ProverState<F> {
+    /// Give the buffers of this state back to `workspace`.
+    pub fn release_into(self, workspace: &mut ProverWorkspace<F>) {
+        workspace.tables.extend(
+            self.flattened_ml_extensions
+                .into_iter()
+                .map(|table| table.evaluations),
         );
+        workspace.scratch = self.scratch;
+    }
+}
+
+impl<F: Field, O: MultilinearOracle<F>> ProverState<F, O> {
+    /// Returns the challenges received so far, in order.
+    ///
+    /// After a complete proof generated by `MLSumcheck::prove_as_subprotocol`, this contains exactly
+    /// `num_vars` challenges, which form the point of the verifier's subclaim.
+    pub fn challenges(&self) -> &[F] {
+        &self.randomness
+    }
+
+    /// Returns the number of variables bound to a challenge so far.
+    ///
+    /// The challenge of a round is only received by the `prove_round` call of the next round, so
+    /// this lags the number of prover messages by one during an interactive proof.
+    pub fn rounds_completed(&self) -> usize {
+        self.randomness.len()
+    }
+
+    /// Returns the number of variables not yet bound to a challenge.
+    pub fn remaining_variables(&self) -> usize {
+        self.num_vars - self.randomness.len()
+    }
+
+    /// Returns, for each product, the evaluations of its multiplicands at the final point.
+    ///
+    /// This is the data consumed by a batch opening of the multiplicands at the point of the subclaim:
+    /// the verifier recomputes the expected evaluation as the sum of the coefficients times the
+    /// products of these evaluations. Panics if the prover has not received the last challenge, as
+    /// is the case after `MLSumcheck::prove_as_subprotocol`.
+    pub fn final_multiplicand_evals(&self) -> Vec<Vec<F>> {
+        if self.round != self.num_vars || self.randomness.len() != self.num_vars {
+            panic!("prover has not finished.");
+        }
+        let r = self.randomness[self.num_vars - 1];
+        // the tables still depend on the last variable, which is fixed here
+        let evals: Vec<F> = self
+            .flattened_ml_extensions
+            .iter()
+            .map(|table| {
+                let (low, high) = (
+                    table.evaluate_on_hypercube(0),
+                    table.evaluate_on_hypercube(1),
+                );
+                low + r * (high - low)
+            })
+            .collect();
+        self.list_of_products
+            .iter()
+            .map(|(_, products)| products.iter().map(|&i| evals[i]).collect())
+            .collect()
+    }
+
+    /// Compute the prover message of the first round, without advancing the state.
+    ///
+    /// The first message does not depend on any verifier challenge, so it can be computed once and
+    /// reused by `MLSumcheck::prove_as_subprotocol_with_round_zero`. The result is only valid for
+    /// the polynomial this state was initialized from.
+    pub fn precompute_round_zero(&self) -> ProverMsg<F> {
+        if self.round != 0 {
+            panic!("prover has already started.");
+        }
+        self.round_message(1)
+    }
+
+    /// Check the prover message of the current round against a brute-force sum over the remaining
+    /// hypercube, and against the previous message evaluated at the last challenge.
+    ///
+    /// Panics if any of them does not match.
+    #[cfg(feature = "prover-self-check")]
+    fn self_check(&mut self, evaluations: &[F]) {
+        let sum = self.sum_of_tables();
+        if evaluations[0] + evaluations[1] != sum {
+            panic!(
+                "prover self-check failed: P(0)+P(1) != sum at round {}",
+                self.round
+            );
+        }
+        if let Some(r) = self.randomness.last() {
+            if !self.last_evaluations.is_empty()
+                && interpolate_uni_poly(&self.last_evaluations, *r) != sum
+            {
+                panic!(
+                    "prover self-check failed: sum does not match the previous round at round {}",
+                    self.round
+                );
+            }
+        }
+        self.last_evaluations.clear();
+        self.last_evaluations.extend_from_slice(evaluations);
+    }
+
+    /// Returns the sum of the polynomial over the boolean hypercube, computed from the loaded tables.
+    ///
+    /// This is the sum that a proof generated from this state will claim. Panics if the prover has
+    /// already started.
+    pub fn claimed_sum(&self) -> F {
+        if self.round != 0 {
+            panic!("prover has already started.");
+        }
+        self.sum_of_tables()
+    }
+
+    /// Sum of the polynomial represented by the current tables over their remaining variables.
+    fn sum_of_tables(&self) -> F {
+        let nv = self
+            .flattened_ml_extensions
+            .first()
+            .map_or(0, |table| table.num_vars());
+        let mut sum = F::zero();
+        for b in 0..1 << nv {
+            for (coefficient, products) in &self.list_of_products {
+                let mut product = *coefficient;
+                for &j in products {
+                    product *= self.flattened_ml_extensions[j].evaluate_on_hypercube(b);
+                }
+                sum += product;
+            }
+        }
+        sum
+    }
+
+    /// Compute the prover message of round `i` from the current tables.
+    fn round_message(&self, i: usize) -> ProverMsg<F> {
+        round_message::<F, O, _>(
+            &self.list_of_products,
+            &self.flattened_ml_extensions,
+            self.num_vars,
+            self.max_multiplicands,
+            i,
+        )
+    }
+}
+
+/// Compute the prover message of round `i` from the tables of the multiplicands.
+fn round_message<F: Field, O: MultilinearOracle<F>, T: Borrow<O> + Sync>(
+    list_of_products: &[(F, Vec<usize>)],
+    flattened_ml_extensions: &[T],
+    nv: usize,
+    degree: usize,
+    i: usize,
+) -> ProverMsg<F> {
+    let mut products_sum = Vec::new();
+    round_message_into::<F, O, T>(
+        list_of_products,
+        flattened_ml_extensions,
+        nv,
+        degree,
+        i,
+        &mut products_sum,
+        &mut Vec::new(),
+    );
+    ProverMsg {
+        evaluations: products_sum,
+    }
+}
+
+/// Compute the evaluations of the prover message of round `i` into `products_sum`, using `product` as scratch space.
+///
+/// The sum of each product is accumulated without its coefficient, which is applied once per round
+/// at the end rather than once per point of the hypercube. `product` is unused with the `parallel`
+/// feature, where each rayon task has its own scratch space.
+#[cfg_attr(feature = "parallel", allow(unused_variables))]
+fn round_message_into<F: Field, O: MultilinearOracle<F>, T: Borrow<O> + Sync>(
+    list_of_products: &[(F, Vec<usize>)],
+    flattened_ml_extensions: &[T],
+    nv: usize,
+    degree: usize, // the degree of univariate polynomial sent by prover at this round
+    i: usize,
+    products_sum: &mut Vec<F>,
+    product: &mut Vec<F>,
+) {
+    // `MLSumcheck::check_provable` returns `Error::DegreeOverflow` before proving if these overflow
+    let width = degree.checked_add(1).expect("the degree overflows");
+    let unscaled_len = list_of_products
+        .len()
+        .checked_mul(width)
+        .expect("the round messages of the products overflow");
+    products_sum.clear();
+    products_sum.resize(width, F::zero());
+
+    // generate sum
+    #[cfg(not(feature = "parallel"))]
+    {
+        // the unscaled sums of all products, followed by the evaluations of the current product
+        product.clear();
+        product.resize(unscaled_len + width, F::zero());
+        let (unscaled_sums, current) = product.split_at_mut(unscaled_len);
+        for b in 0..1 << (nv - i) {
+            accumulate_point::<F, O, T>(
+                list_of_products,
+                flattened_ml_extensions,
+                b,
+                unscaled_sums,
+                current,
+            );
+        }
+        scale_products(list_of_products, unscaled_sums, products_sum);
+    }
+
+    // When rayon is used, the `fold` operation results in a iterator of `Vec<F>` rather than a single `Vec<F>`. In this case, we simply need to sum them.
+    #[cfg(feature = "parallel")]
+    {
+        let zeros = || (vec![F::zero(); unscaled_len], vec![F::zero(); width]);
+        let unscaled_sums = ark_std::cfg_into_iter!(0..1 << (nv - i), 1 << 10)
+            .fold(zeros, |(mut unscaled_sums, mut current), b| {
+                accumulate_point::<F, O, T>(
+                    list_of_products,
+                    flattened_ml_extensions,
+                    b,
+                    &mut unscaled_sums,
+                    &mut current,
+                );
+                (unscaled_sums, current)
+            })
+            .map(|scratch| scratch.0)
+            .reduce(
+                || vec![F::zero(); unscaled_len],
+                |mut overall_sums, sublist_sums| {
+                    overall_sums
+                        .iter_mut()
+                        .zip(sublist_sums.iter())
+                        .for_each(|(f, s)| *f += s);
+                    overall_sums
+                },
+            );
+        scale_products(list_of_products, &unscaled_sums, products_sum);
+    }
+}
+
+/// Add the unscaled contribution of the point `b` of the remaining hypercube to the sum of each product.
+///
+/// `unscaled_sums` holds one chunk of `current.len()` evaluations per product.
+#[inline]
+fn accumulate_point<F: Field, O: MultilinearOracle<F>, T: Borrow<O>>(
+    list_of_products: &[(F, Vec<usize>)],
+    flattened_ml_extensions: &[T],
+    b: usize,
+    unscaled_sums: &mut [F],
+    current: &mut [F],
+) {
+    let width = current.len();
+    for ((_, products), sums) in list_of_products.iter().zip(unscaled_sums.chunks_mut(width)) {
+        let mut multiplicands = products.iter();
+        match multiplicands.next() {
+            // the first multiplicand is written directly, which saves multiplying by the coefficient
+            Some(&first) => {
+                let table: &O = flattened_ml_extensions[first].borrow();
+                let mut start = table.evaluate_on_hypercube(b << 1);
+                let step = table.evaluate_on_hypercube((b << 1) + 1) - start;
+                for p in current.iter_mut() {
+                    *p = start;
+                    start += step;
+                }
+            }
+            None => current.fill(F::one()),
+        }
+        for &jth_product in multiplicands {
+            let table: &O = flattened_ml_extensions[jth_product].borrow();
+            let mut start = table.evaluate_on_hypercube(b << 1);
+            let step = table.evaluate_on_hypercube((b << 1) + 1) - start;
+            for p in current.iter_mut() {
+                *p *= start;
+                start += step;
+            }
+        }
+        for (sum, p) in sums.iter_mut().zip(current.iter()) {
+            *sum += p;
+        }
+    }
+}
 
-        ProverMsg {
-            evaluations: products_sum,
+/// Add the sum of each product, scaled by its coefficient, to `products_sum`.
+fn scale_products<F: Field>(
+    list_of_products: &[(F, Vec<usize>)],
+    unscaled_sums: &[F],
+    products_sum: &mut [F],
+) {
+    for ((coefficient, _), sums) in list_of_products
+        .iter()
+        .zip(unscaled_sums.chunks(products_sum.len()))
+    {
+        for (total, sum) in products_sum.iter_mut().zip(sums) {
+            *total += *coefficient * sum;
         }
     }
 }
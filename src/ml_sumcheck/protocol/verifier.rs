@@ -2,8 +2,11 @@
 use crate::ml_sumcheck::data_structures::PolynomialInfo;
 use crate::ml_sumcheck::protocol::prover::ProverMsg;
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
-use ark_ff::Field;
+use crate::rng::FeedableRNG;
+use crate::RoundCheckFailure;
+use ark_ff::{batch_inversion, Field};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::boxed::Box;
 use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 
@@ -14,6 +17,54 @@ pub struct VerifierMsg<F: Field> {
     pub randomness: F,
 }
 
+/// Method used by the verifier to evaluate the round polynomials at the challenges.
+///
+/// Both methods compute the same field elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Lagrange form, with a single inversion for all rounds
+    Lagrange,
+    /// Newton form from divided differences, evaluated by Horner's rule
+    Newton,
+}
+
+/// Options of `MLSumcheck::verify_with_options`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// how the round polynomials are evaluated
+    pub interpolation: Interpolation,
+    /// whether prover messages may have more than the `max_multiplicands + 1` evaluations needed,
+    /// as sent by provers that over-provision
+    ///
+    /// Only the first `max_multiplicands + 1` evaluations are used; all of them are fed to the
+    /// transcript as received.
+    pub tolerate_extra_evaluations: bool,
+    /// whether the extra evaluations tolerated by `tolerate_extra_evaluations` must be those of the
+    /// round polynomial, instead of being ignored
+    pub cross_check_extra_evaluations: bool,
+}
+
+impl Default for VerifyOptions {
+    /// The options used by `MLSumcheck::verify`.
+    fn default() -> Self {
+        Self {
+            interpolation: Interpolation::Lagrange,
+            tolerate_extra_evaluations: false,
+            cross_check_extra_evaluations: false,
+        }
+    }
+}
+
+/// What a verifier of the first rounds of a proof hands to the verifier of the remaining rounds,
+/// along with the transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierHandoff<F: Field> {
+    /// the value the round polynomial of the next round must sum to on `{0, 1}`
+    pub expected_sum: F,
+    /// the challenges of the rounds verified so far
+    pub point: Vec<F>,
+}
+
 /// Verifier State
 pub struct VerifierState<F: Field> {
     round: usize,
@@ -33,6 +84,73 @@ pub struct SubClaim<F: Field> {
     pub expected_evaluation: F,
 }
 
+impl<F: Field> SubClaim<F> {
+    /// check the subclaim against the openings of the multiplicands at `self.point`
+    ///
+    /// `openings[i][j]` is the evaluation of the `j`-th multiplicand of the `i`-th product, and
+    /// `coeffs[i]` is the coefficient of the `i`-th product. Returns whether
+    /// `sum_i coeffs[i] * prod_j openings[i][j]` equals `self.expected_evaluation`, or
+    /// `Error::OtherError` if `coeffs` and `openings` have different lengths.
+    pub fn check_openings(&self, coeffs: &[F], openings: &[Vec<F>]) -> Result<bool, crate::Error> {
+        if coeffs.len() != openings.len() {
+            return Err(crate::Error::OtherError(format!(
+                "got {} coefficients for {} products",
+                coeffs.len(),
+                openings.len()
+            )));
+        }
+        let evaluation: F = coeffs
+            .iter()
+            .zip(openings.iter())
+            .map(|(c, product)| *c * product.iter().product::<F>())
+            .sum();
+        Ok(evaluation == self.expected_evaluation)
+    }
+
+    /// whether the subclaim holds, given the openings of the multiplicands at `self.point`
+    ///
+    /// `evaluations[i][j]` is the evaluation of the `j`-th multiplicand of the `i`-th product, as
+    /// obtained from the openings of a polynomial commitment, and `coeffs[i]` is the coefficient of
+    /// the `i`-th product. This is `check_openings` with the arguments in the order of the openings.
+    pub fn finalize_with(
+        &self,
+        evaluations: &[Vec<F>],
+        coeffs: &[F],
+    ) -> Result<bool, crate::Error> {
+        self.check_openings(coeffs, evaluations)
+    }
+
+    /// combine with a subclaim over disjoint variables into a subclaim over all of them
+    ///
+    /// The point of `other` comes after the point of `self`, and the expected evaluations are
+    /// multiplied. This is only sound if the polynomial is the tensor product `f(x) * g(y)`, where
+    /// `self` is a subclaim about `f` and `other` a subclaim about `g`, as for a
+    /// `data_structures::TensorProduct`.
+    pub fn concat(&self, other: &SubClaim<F>) -> SubClaim<F> {
+        SubClaim {
+            point: self.point.iter().chain(&other.point).copied().collect(),
+            expected_evaluation: self.expected_evaluation * other.expected_evaluation,
+        }
+    }
+
+    /// whether `actual`, the evaluation of the polynomial at `self.point`, is the expected one
+    pub fn verify_value(&self, actual: F) -> bool {
+        actual == self.expected_evaluation
+    }
+
+    /// same as `verify_value`, but returns `Error::Reject` on mismatch
+    pub fn verify_value_err(&self, actual: F) -> Result<(), crate::Error> {
+        if self.verify_value(actual) {
+            Ok(())
+        } else {
+            Err(crate::Error::Reject(Some(format!(
+                "evaluation {} at the subclaim point does not match the expected evaluation {}",
+                actual, self.expected_evaluation
+            ))))
+        }
+    }
+}
+
 impl<F: Field> IPForMLSumcheck<F> {
     /// initialize the verifier
     pub fn verifier_init(index_info: &PolynomialInfo) -> VerifierState<F> {
@@ -55,6 +173,17 @@ impl<F: Field> IPForMLSumcheck<F> {
         prover_msg: ProverMsg<F>,
         verifier_state: &mut VerifierState<F>,
         rng: &mut R,
+    ) -> Option<VerifierMsg<F>> {
+        let challenge = Self::sample_round(rng).randomness;
+        Self::verify_round_with_challenge(prover_msg, verifier_state, challenge)
+    }
+
+    /// same as `verify_round`, but the challenge of this round is `challenge` instead of being
+    /// sampled
+    pub fn verify_round_with_challenge(
+        prover_msg: ProverMsg<F>,
+        verifier_state: &mut VerifierState<F>,
+        challenge: F,
     ) -> Option<VerifierMsg<F>> {
         if verifier_state.finished {
             panic!("Incorrect verifier state: Verifier is already finished.");
@@ -63,7 +192,9 @@ impl<F: Field> IPForMLSumcheck<F> {
         // Now, verifier should check if the received P(0) + P(1) = expected. The check is moved to
         // `check_and_generate_subclaim`, and will be done after the last round.
 
-        let msg = Self::sample_round(rng);
+        let msg = VerifierMsg {
+            randomness: challenge,
+        };
         verifier_state.randomness.push(msg.randomness);
         verifier_state
             .polynomials_received
@@ -136,37 +267,132 @@ impl<F: Field> IPForMLSumcheck<F> {
     /// If the asserted sum is correct, then the multilinear polynomial evaluated at `subclaim.point`
     /// is `subclaim.expected_evaluation`. Otherwise, it is highly unlikely that those two will be equal.
     /// Larger field size guarantees smaller soundness error.
+    ///
+    /// Returns `Error::RoundCheckFailed`, with the failing round, if `P(0) + P(1) = expected` does
+    /// not hold for some round.
+    ///
+    /// The check `P(0) + P(1) = expected` done here for every round is the core soundness check. Any
+    /// alternative encoding of the prover messages must be decoded to the full list of evaluations before
+    /// reaching this function, so that the check is never skipped. Note that an encoding where `P(1)` is
+    /// reconstructed as `expected - P(0)` makes this check hold by construction; such an encoding is not
+    /// provided by this crate.
     pub fn check_and_generate_subclaim(
         verifier_state: VerifierState<F>,
         asserted_sum: F,
     ) -> Result<SubClaim<F>, crate::Error> {
-        if !verifier_state.finished {
-            panic!("Verifier has not finished.");
+        Self::check_and_generate_subclaim_with_counter(verifier_state, asserted_sum, &mut ())
+    }
+
+    /// same as `check_and_generate_subclaim`, but records the field operations into `counter`
+    pub(crate) fn check_and_generate_subclaim_with_counter<C: OpCounter>(
+        verifier_state: VerifierState<F>,
+        asserted_sum: F,
+        counter: &mut C,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        let expected = Self::check_and_collect_expected(&verifier_state, asserted_sum, counter)?;
+        Ok(SubClaim {
+            point: verifier_state.randomness,
+            expected_evaluation: expected.last().copied().unwrap_or(asserted_sum),
+        })
+    }
+
+    /// same as `check_and_generate_subclaim`, but evaluates the round polynomials as set by `options`
+    ///
+    /// With `options.cross_check_extra_evaluations`, returns `Error::Reject` if an extra evaluation
+    /// of a round is not the evaluation of the round polynomial at its point.
+    pub fn check_and_generate_subclaim_with_options(
+        mut verifier_state: VerifierState<F>,
+        asserted_sum: F,
+        options: &VerifyOptions,
+    ) -> Result<SubClaim<F>, crate::Error> {
+        if options.tolerate_extra_evaluations {
+            let num_evaluations = verifier_state.max_multiplicands + 1;
+            for (i, evaluations) in verifier_state.polynomials_received.iter_mut().enumerate() {
+                if evaluations.len() <= num_evaluations {
+                    continue;
+                }
+                if options.cross_check_extra_evaluations {
+                    let (required, extra) = evaluations.split_at(num_evaluations);
+                    let mut point = F::from(num_evaluations as u64);
+                    for e in extra {
+                        if interpolate_uni_poly(required, point) != *e {
+                            return Err(crate::Error::Reject(Some(format!(
+                                "extra evaluation at {} of round {} does not match the round polynomial",
+                                point, i
+                            ))));
+                        }
+                        point += F::one();
+                    }
+                }
+                evaluations.truncate(num_evaluations);
+            }
         }
+        let expected = Self::check_and_collect_expected_with(
+            &verifier_state,
+            asserted_sum,
+            &mut (),
+            options.interpolation,
+        )?;
+        Ok(SubClaim {
+            point: verifier_state.randomness,
+            expected_evaluation: expected.last().copied().unwrap_or(asserted_sum),
+        })
+    }
+
+    /// check all rounds, and return the expected sum of every round after its challenge is applied,
+    /// i.e. `P_i(r_i)` for each round `i`
+    pub(crate) fn check_and_collect_expected<C: OpCounter>(
+        verifier_state: &VerifierState<F>,
+        asserted_sum: F,
+        counter: &mut C,
+    ) -> Result<Vec<F>, crate::Error> {
+        Self::check_and_collect_expected_with(
+            verifier_state,
+            asserted_sum,
+            counter,
+            Interpolation::Lagrange,
+        )
+    }
+
+    /// same as `check_and_collect_expected`, with the given interpolation method
+    fn check_and_collect_expected_with<C: OpCounter>(
+        verifier_state: &VerifierState<F>,
+        asserted_sum: F,
+        counter: &mut C,
+        interpolation: Interpolation,
+    ) -> Result<Vec<F>, crate::Error> {
+        check_finished(verifier_state)?;
 
         let mut expected = asserted_sum;
-        if verifier_state.polynomials_received.len() != verifier_state.nv {
-            panic!("insufficient rounds");
+        for (i, evaluations) in verifier_state.polynomials_received.iter().enumerate() {
+            check_num_evaluations(evaluations, verifier_state.max_multiplicands, i)?;
         }
+        let interpolations = match interpolation {
+            // the interpolations only depend on the prover messages and the challenges, so they are
+            // computed for all rounds at once, with a single inversion
+            Interpolation::Lagrange => batch_interpolate_uni_polys(
+                &verifier_state.polynomials_received,
+                &verifier_state.randomness,
+                counter,
+            ),
+            Interpolation::Newton => newton_interpolate_uni_polys(
+                &verifier_state.polynomials_received,
+                &verifier_state.randomness,
+                counter,
+            ),
+        };
         for i in 0..verifier_state.nv {
             let evaluations = &verifier_state.polynomials_received[i];
-            if evaluations.len() != verifier_state.max_multiplicands + 1 {
-                panic!("incorrect number of evaluations");
-            }
             let p0 = evaluations[0];
             let p1 = evaluations[1];
+            counter.add(1);
             if p0 + p1 != expected {
-                return Err(crate::Error::Reject(Some(
-                    format!("Prover message is not consistent with the claim. Error at round {}, got {:?}, expected {:?}", i, p0 + p1, expected).into(),
-                )));
+                return Err(round_check_failed(i, expected, p0, p1));
             }
-            expected = interpolate_uni_poly(evaluations, verifier_state.randomness[i]);
+            expected = interpolations[i];
         }
 
-        Ok(SubClaim {
-            point: verifier_state.randomness,
-            expected_evaluation: expected,
-        })
+        Ok(interpolations)
     }
 
     /// verify the sumcheck phase, and generate the subclaim
@@ -178,34 +404,24 @@ impl<F: Field> IPForMLSumcheck<F> {
         verifier_state: (VerifierState<F>, VerifierState<F>),
         asserted_sum: F,
     ) -> Result<SubClaim<F>, crate::Error> {
-        if !verifier_state.0.finished || !verifier_state.1.finished {
-            panic!("Verifier has not finished.");
+        check_finished(&verifier_state.0)?;
+        check_finished(&verifier_state.1)?;
+        if verifier_state.1.nv == 0 || verifier_state.0.nv <= verifier_state.1.nv {
+            return Err(crate::Error::Reject(Some(
+                "the first polynomial should have more variables than the second one".into(),
+            )));
         }
         let mut expected = asserted_sum;
-        if verifier_state.0.polynomials_received.len() != verifier_state.0.nv {
-            panic!("insufficient rounds");
-        }
-        if verifier_state.1.polynomials_received.len() != verifier_state.1.nv {
-            panic!("insufficient rounds");
-        }
         for i in 0..verifier_state.1.nv {
             let evaluations_0 = &verifier_state.0.polynomials_received[i];
-            if evaluations_0.len() != verifier_state.0.max_multiplicands + 1 {
-                panic!("incorrect number of evaluations");
-            }
+            check_num_evaluations(evaluations_0, verifier_state.0.max_multiplicands, i)?;
             let evaluations_1 = &verifier_state.1.polynomials_received[i];
-            if evaluations_1.len() != verifier_state.1.max_multiplicands + 1 {
-                panic!("incorrect number of evaluations");
-            }
-            let sum = evaluations_0[0] + evaluations_1[0] + evaluations_0[1] + evaluations_1[1];
-            if sum != expected {
-                return Err(crate::Error::Reject(Some(
-                    format!(
-                        "Prover message is not consistent with the claim. Error at round {}",
-                        i
-                    )
-                    .into(),
-                )));
+            check_num_evaluations(evaluations_1, verifier_state.1.max_multiplicands, i)?;
+            // the combined round polynomial is the sum of the two round polynomials
+            let g0 = evaluations_0[0] + evaluations_1[0];
+            let g1 = evaluations_0[1] + evaluations_1[1];
+            if g0 + g1 != expected {
+                return Err(round_check_failed(i, expected, g0, g1));
             }
             expected = interpolate_uni_poly(evaluations_0, verifier_state.0.randomness[i])
                 + interpolate_uni_poly(evaluations_1, verifier_state.1.randomness[i]);
@@ -218,14 +434,14 @@ impl<F: Field> IPForMLSumcheck<F> {
         expected -= c;
         for i in verifier_state.1.nv..verifier_state.0.nv {
             let evaluations_0 = &verifier_state.0.polynomials_received[i];
-            if evaluations_0.len() != verifier_state.0.max_multiplicands + 1 {
-                panic!("incorrect number of evaluations");
-            }
-            let sum = evaluations_0[0] + evaluations_0[1];
-            if sum != expected {
-                return Err(crate::Error::Reject(Some(
-                    format!("Prover message is not consistent with the claim. Error at round {}, got {:?}, expected {:?}", i, sum, expected).into(),
-                )));
+            check_num_evaluations(evaluations_0, verifier_state.0.max_multiplicands, i)?;
+            if evaluations_0[0] + evaluations_0[1] != expected {
+                return Err(round_check_failed(
+                    i,
+                    expected,
+                    evaluations_0[0],
+                    evaluations_0[1],
+                ));
             }
             expected = interpolate_uni_poly(evaluations_0, verifier_state.0.randomness[i]);
         }
@@ -246,6 +462,372 @@ impl<F: Field> IPForMLSumcheck<F> {
             randomness: F::rand(rng),
         }
     }
+
+    /// same as `sample_round`, but returns `Error::RNGError` instead of panicking if `rng` fails to
+    /// fill bytes
+    ///
+    /// `RngCore::next_u32` and `RngCore::next_u64` cannot report errors, so only the errors of
+    /// `RngCore::try_fill_bytes` are caught. The message is the one of `sample_round` otherwise.
+    pub fn try_sample_round<R: RngCore>(rng: &mut R) -> Result<VerifierMsg<F>, crate::Error> {
+        let mut rng = CheckedRng { rng, error: None };
+        let randomness = F::rand(&mut rng);
+        match rng.error {
+            Some(e) => Err(e.into()),
+            None => Ok(VerifierMsg { randomness }),
+        }
+    }
+}
+
+/// `RngCore` that records the first error of `try_fill_bytes` instead of panicking.
+struct CheckedRng<'a, R: RngCore> {
+    rng: &'a mut R,
+    error: Option<ark_std::rand::Error>,
+}
+
+impl<R: RngCore> RngCore for CheckedRng<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.rng.try_fill_bytes(dest) {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Counts the field operations performed by the verifier.
+///
+/// The implementation for `()` does nothing, so that the uninstrumented path has no overhead.
+pub(crate) trait OpCounter {
+    /// record `n` additions (or subtractions)
+    fn add(&mut self, n: usize);
+    /// record `n` multiplications
+    fn mul(&mut self, n: usize);
+    /// record `n` inversions
+    fn inv(&mut self, n: usize);
+}
+
+impl OpCounter for () {
+    #[inline(always)]
+    fn add(&mut self, _n: usize) {}
+    #[inline(always)]
+    fn mul(&mut self, _n: usize) {}
+    #[inline(always)]
+    fn inv(&mut self, _n: usize) {}
+}
+
+/// Number of field operations performed by a verification.
+///
+/// A division is counted as one inversion and one multiplication.
+#[cfg(feature = "stats")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifyStats {
+    /// number of field multiplications
+    pub multiplications: usize,
+    /// number of field additions and subtractions
+    pub additions: usize,
+    /// number of field inversions
+    pub inversions: usize,
+}
+
+#[cfg(feature = "stats")]
+impl OpCounter for VerifyStats {
+    fn add(&mut self, n: usize) {
+        self.additions += n;
+    }
+    fn mul(&mut self, n: usize) {
+        self.multiplications += n;
+    }
+    fn inv(&mut self, n: usize) {
+        self.inversions += n;
+    }
+}
+
+/// Evaluate each univariate polynomial `polys[i]`, given by its evaluations at `0, ..., polys[i].len() - 1`,
+/// at `points[i]`.
+///
+/// This computes the same values as `interpolate_uni_poly`, in barycentric form:
+///
+/// $$p(r) = \prod_{k}(r - k)\cdot\sum_{j}\frac{p_j}{(r - j)\prod_{k\neq j}(j - k)}$$
+///
+/// All the denominators, over all polynomials, are inverted at once with `ark_ff::batch_inversion`.
+/// The operation counts of `batch_inversion` are reported as `3n + 1` multiplications and one inversion
+/// for `n` denominators.
+pub(crate) fn batch_interpolate_uni_polys<F: Field, C: OpCounter>(
+    polys: &[Vec<F>],
+    points: &[F],
+    counter: &mut C,
+) -> Vec<F> {
+    assert_eq!(polys.len(), points.len());
+    let max_len = polys.iter().map(|p| p.len()).max().unwrap_or(0);
+
+    // factorials[i] = i!
+    let mut factorials = Vec::with_capacity(max_len);
+    factorials.push(F::one());
+    for i in 1..max_len {
+        factorials.push(factorials[i - 1] * F::from(i as u64));
+        counter.mul(1);
+    }
+
+    // differences[i] = r - j, denominators[i] = (r - j) * \prod_{k != j} (j - k) for every polynomial
+    // and j, flattened
+    let total_len: usize = polys.iter().map(|p| p.len()).sum();
+    let mut differences = Vec::with_capacity(total_len);
+    let mut denominators = Vec::with_capacity(total_len);
+    for (p, &r) in polys.iter().zip(points.iter()) {
+        let len = p.len();
+        for j in 0..len {
+            // \prod_{k != j} (j - k) = (-1)^(len - 1 - j) * j! * (len - 1 - j)!
+            let mut weight = factorials[j] * factorials[len - 1 - j];
+            if (len - 1 - j) % 2 == 1 {
+                weight = -weight;
+            }
+            let difference = r - F::from(j as u64);
+            differences.push(difference);
+            denominators.push(weight * difference);
+            counter.add(1);
+            counter.mul(2);
+        }
+    }
+    batch_inversion(&mut denominators);
+    counter.mul(3 * total_len + 1);
+    counter.inv(1);
+
+    let mut results = Vec::with_capacity(polys.len());
+    let mut offset = 0;
+    for p in polys {
+        let len = p.len();
+        let differences = &differences[offset..offset + len];
+        let inverses = &denominators[offset..offset + len];
+        offset += len;
+
+        // the point is one of the nodes, so the value is already known
+        if let Some(j) = differences.iter().position(|d| d.is_zero()) {
+            results.push(p[j]);
+            continue;
+        }
+        let mut prod = F::one();
+        let mut sum = F::zero();
+        for j in 0..len {
+            prod *= differences[j];
+            sum += p[j] * inverses[j];
+        }
+        counter.add(len);
+        counter.mul(2 * len + 1);
+        results.push(prod * sum);
+    }
+    results
+}
+
+/// evaluate each round polynomial, given by its evaluations at `0, 1, ..., d`, at its challenge
+/// using the Newton form
+///
+/// The divisors of the divided differences only depend on the degree, so their inverses are shared
+/// by all rounds of the same degree.
+pub(crate) fn newton_interpolate_uni_polys<F: Field, C: OpCounter>(
+    polynomials: &[Vec<F>],
+    points: &[F],
+    counter: &mut C,
+) -> Vec<F> {
+    let max_len = polynomials.iter().map(Vec::len).max().unwrap_or(0);
+    // inverses of 1, 2, ..., max_len - 1
+    let mut inverses: Vec<F> = (1..max_len as u64).map(F::from).collect();
+    batch_inversion(&mut inverses);
+    counter.inv(1);
+    polynomials
+        .iter()
+        .zip(points)
+        .map(|(evaluations, &x)| newton_evaluate(evaluations, x, &inverses, counter))
+        .collect()
+}
+
+/// evaluate the polynomial given by its evaluations at `0, 1, ..., d` at `x`, where `inverses[k - 1]`
+/// is the inverse of `k`
+fn newton_evaluate<F: Field, C: OpCounter>(
+    evaluations: &[F],
+    x: F,
+    inverses: &[F],
+    counter: &mut C,
+) -> F {
+    let n = evaluations.len();
+    // divided differences over the nodes 0, 1, ..., d, where nodes `k` apart differ by `k`
+    let mut coefficients = evaluations.to_vec();
+    for k in 1..n {
+        for i in (k..n).rev() {
+            coefficients[i] = (coefficients[i] - coefficients[i - 1]) * inverses[k - 1];
+        }
+        counter.add(n - k);
+        counter.mul(n - k);
+    }
+    // Horner's rule on c_0 + (x - 0)(c_1 + (x - 1)(c_2 + ...))
+    let mut result = F::zero();
+    for (i, c) in coefficients.iter().enumerate().rev() {
+        result = result * (x - F::from(i as u64)) + c;
+    }
+    counter.add(2 * n);
+    counter.mul(n);
+    result
+}
+
+/// Returns the monomial coefficients, constant term first, of the polynomial of degree less than
+/// `evaluations.len()` that takes the value `evaluations[i]` at `i`.
+pub(crate) fn evaluations_to_coefficients<F: Field>(evaluations: &[F]) -> Vec<F> {
+    let n = evaluations.len();
+    let mut inverses: Vec<F> = (1..n as u64).map(F::from).collect();
+    batch_inversion(&mut inverses);
+    // divided differences, as in `newton_evaluate`
+    let mut newton = evaluations.to_vec();
+    for k in 1..n {
+        for i in (k..n).rev() {
+            newton[i] = (newton[i] - newton[i - 1]) * inverses[k - 1];
+        }
+    }
+    // expand c_0 + (x - 0)(c_1 + (x - 1)(c_2 + ...)) from the innermost factor
+    let mut coefficients = vec![F::zero(); n];
+    for (k, c) in newton.iter().enumerate().rev() {
+        let node = F::from(k as u64);
+        for j in (1..n).rev() {
+            coefficients[j] = coefficients[j - 1] - node * coefficients[j];
+        }
+        coefficients[0] = *c - node * coefficients[0];
+    }
+    coefficients
+}
+
+/// Returns the evaluations at `0, 1, ..., coefficients.len() - 1` of the polynomial with monomial
+/// coefficients `coefficients`, constant term first.
+pub(crate) fn coefficients_to_evaluations<F: Field>(coefficients: &[F]) -> Vec<F> {
+    (0..coefficients.len() as u64)
+        .map(|x| horner_evaluate(coefficients, F::from(x)))
+        .collect()
+}
+
+/// evaluate the polynomial with monomial coefficients `coefficients`, constant term first, at `x`
+pub(crate) fn horner_evaluate<F: Field>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |result, c| result * x + c)
+}
+
+/// Returns `Error::Reject` unless the verifier has received all rounds.
+fn check_finished<F: Field>(verifier_state: &VerifierState<F>) -> Result<(), crate::Error> {
+    if !verifier_state.finished || verifier_state.polynomials_received.len() != verifier_state.nv {
+        return Err(crate::Error::Reject(Some(
+            "verifier has not finished".into(),
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `Error::RoundCheckFailed` for round `round`, whose round polynomial `g` has `g(0) = g0`
+/// and `g(1) = g1`.
+pub(crate) fn round_check_failed<F: Field>(
+    round: usize,
+    expected: F,
+    g0: F,
+    g1: F,
+) -> crate::Error {
+    crate::Error::RoundCheckFailed(Box::new(RoundCheckFailure {
+        round,
+        expected: format!("{}", expected),
+        g0: format!("{}", g0),
+        g1: format!("{}", g1),
+    }))
+}
+
+/// Returns `Error::Reject` unless the prover message of round `i` has `max_multiplicands + 1`
+/// evaluations, and at least two of them.
+pub(crate) fn check_num_evaluations<F: Field>(
+    evaluations: &[F],
+    max_multiplicands: usize,
+    i: usize,
+) -> Result<(), crate::Error> {
+    if evaluations.len() < 2 || evaluations.len() - 1 != max_multiplicands {
+        return Err(crate::Error::Reject(Some(format!(
+            "incorrect number of evaluations at round {}",
+            i
+        ))));
+    }
+    Ok(())
+}
+
+/// Feeds the prover message of a round to `fs_rng`. If `with_info`, `polynomial_info` is fed in the
+/// same batch, since no challenge is sampled between the polynomial info and the first message.
+pub(crate) fn feed_round_message<F: Field>(
+    fs_rng: &mut impl FeedableRNG<Error = crate::Error>,
+    polynomial_info: &PolynomialInfo,
+    prover_msg: &ProverMsg<F>,
+    with_info: bool,
+) -> Result<(), crate::Error> {
+    if with_info {
+        fs_rng.feed_batch(&[polynomial_info, prover_msg])
+    } else {
+        fs_rng.feed(prover_msg)
+    }
+}
+
+/// Evaluate at `point` the round polynomial given by its `evaluations` at `0, 1, ..., d`.
+///
+/// This is the univariate polynomial of degree at most `evaluations.len() - 1` through the
+/// evaluations, in Lagrange form. The verifier evaluates all rounds in a batch with
+/// `batch_interpolate_uni_polys` instead, which shares the inversions but gives the same value,
+/// so an in-circuit verifier mirroring this formula accepts the same proofs.
+pub fn interpolate_and_evaluate<F: Field>(evaluations: &[F], point: F) -> F {
+    interpolate_uni_poly(evaluations, point)
+}
+
+/// Returns the evaluations at `point` of the `degree + 1` Lagrange basis polynomials over the nodes
+/// `0, 1, ..., degree`.
+///
+/// The inner product of the result with the evaluations of a round polynomial of degree at most
+/// `degree` is `interpolate_and_evaluate(evaluations, point)`. Coefficient `i` is
+/// `\prod_{j != i} (point - j) / (i - j)`, computed with prefix and suffix products and a single
+/// inversion.
+pub fn lagrange_coefficients_at<F: Field>(degree: usize, point: F) -> Vec<F> {
+    let len = degree + 1;
+    let differences: Vec<F> = (0..len).map(|j| point - F::from(j as u64)).collect();
+    // suffix[i] = \prod_{j > i} (point - j)
+    let mut suffix = vec![F::one(); len];
+    for i in (0..degree).rev() {
+        suffix[i] = suffix[i + 1] * differences[i + 1];
+    }
+    // \prod_{j != i} (i - j) = (-1)^(degree - i) i! (degree - i)!
+    let mut factorials = vec![F::one(); len];
+    for i in 1..len {
+        factorials[i] = factorials[i - 1] * F::from(i as u64);
+    }
+    let mut denominators: Vec<F> = (0..len)
+        .map(|i| {
+            let denominator = factorials[i] * factorials[degree - i];
+            if (degree - i) % 2 == 1 {
+                -denominator
+            } else {
+                denominator
+            }
+        })
+        .collect();
+    batch_inversion(&mut denominators);
+    let mut prefix = F::one();
+    denominators
+        .iter()
+        .zip(suffix)
+        .zip(differences)
+        .map(|((inverse, suffix), difference)| {
+            let coefficient = prefix * suffix * inverse;
+            prefix *= difference;
+            coefficient
+        })
+        .collect()
 }
 
 /// interpolate the *unique* univariate polynomial of degree *at most*
@@ -398,7 +980,10 @@ fn u64_factorial(a: usize) -> u64 {
 
 #[cfg(test)]
 mod test {
-    use crate::ml_sumcheck::protocol::verifier::interpolate_uni_poly;
+    use crate::ml_sumcheck::protocol::verifier::{
+        batch_interpolate_uni_polys, interpolate_and_evaluate, interpolate_uni_poly,
+        lagrange_coefficients_at, newton_interpolate_uni_polys,
+    };
     use ark_poly::univariate::DensePolynomial;
     use ark_poly::DenseUVPolynomial;
     use ark_poly::Polynomial;
@@ -446,4 +1031,80 @@ mod test {
             .collect::<Vec<F>>();
         assert_eq!(interpolate_uni_poly(&evals, F::from(3)), F::from(9));
     }
+
+    #[test]
+    fn test_lagrange_coefficients_at() {
+        let mut prng = ark_std::test_rng();
+        for degree in 0..8 {
+            let evaluations: Vec<F> = (0..=degree).map(|_| F::rand(&mut prng)).collect();
+            let point = F::rand(&mut prng);
+            let coefficients = lagrange_coefficients_at(degree, point);
+            assert_eq!(coefficients.len(), degree + 1);
+            let dot: F = coefficients
+                .iter()
+                .zip(&evaluations)
+                .map(|(c, e)| *c * e)
+                .sum();
+            assert_eq!(dot, interpolate_and_evaluate(&evaluations, point));
+            // at a node, the coefficients select its evaluation
+            let node = lagrange_coefficients_at(degree, F::from((degree / 2) as u64));
+            for (i, c) in node.iter().enumerate() {
+                assert_eq!(*c, F::from((i == degree / 2) as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolate_and_evaluate() {
+        let mut prng = ark_std::test_rng();
+        for len in 1..8 {
+            let evaluations: Vec<F> = (0..len).map(|_| F::rand(&mut prng)).collect();
+            let point = F::rand(&mut prng);
+            // sum_i y_i * prod_{j != i} (point - j) / (i - j)
+            let naive: F = (0..len)
+                .map(|i| {
+                    let (num, den) = (0..len).filter(|&j| j != i).fold(
+                        (F::from(1u64), F::from(1u64)),
+                        |(num, den), j| {
+                            let (i, j) = (F::from(i as u64), F::from(j as u64));
+                            (num * (point - j), den * (i - j))
+                        },
+                    );
+                    evaluations[i] * num / den
+                })
+                .sum();
+            assert_eq!(interpolate_and_evaluate(&evaluations, point), naive);
+            // at a node, the value is the evaluation
+            let node = len / 2;
+            assert_eq!(
+                interpolate_and_evaluate(&evaluations, F::from(node as u64)),
+                evaluations[node]
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_interpolation() {
+        let mut prng = ark_std::test_rng();
+
+        let mut polys = Vec::new();
+        let mut points = Vec::new();
+        for &len in &[1usize, 2, 9, 9, 20, 33, 40] {
+            polys.push((0..len).map(|_| F::rand(&mut prng)).collect::<Vec<F>>());
+            points.push(F::rand(&mut prng));
+        }
+        // points on the nodes
+        polys.push((0..9).map(|_| F::rand(&mut prng)).collect());
+        points.push(F::from(4u64));
+        polys.push((0..9).map(|_| F::rand(&mut prng)).collect());
+        points.push(F::from(0u64));
+
+        let batched = batch_interpolate_uni_polys(&polys, &points, &mut ());
+        for ((p, &r), value) in polys.iter().zip(points.iter()).zip(batched.iter()) {
+            assert_eq!(interpolate_uni_poly(p, r), *value);
+        }
+
+        let newton = newton_interpolate_uni_polys(&polys, &points, &mut ());
+        assert_eq!(newton, batched);
+    }
 }
@@ -1,70 +1,38 @@
-use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use crate::ml_sumcheck::accumulator::VerificationAccumulator;
+use crate::ml_sumcheck::commitment::CommitmentScheme;
+use crate::ml_sumcheck::data_structures::{
+    ChallengePower, ListOfProductsOfPolynomials, PolynomialInfo, TensorProduct,
+};
+use crate::ml_sumcheck::equality_check::{eq_eval, precompute_eq_table, EqualityCheck};
+use crate::ml_sumcheck::incremental::IncrementalVerifier;
+use crate::ml_sumcheck::interactive::SumcheckProver;
+use crate::ml_sumcheck::merkle::{merkle_openings, merkle_root};
+use crate::ml_sumcheck::protocol::oracle::{MultilinearOracle, Reflected, Shifted};
+use crate::ml_sumcheck::protocol::prover::{ProofEncoding, ProverMsg, ProverWorkspace};
+use crate::ml_sumcheck::protocol::verifier::{
+    interpolate_and_evaluate, Interpolation, VerifierMsg, VerifyOptions,
+};
 use crate::ml_sumcheck::protocol::IPForMLSumcheck;
-use crate::ml_sumcheck::MLSumcheck;
-use crate::rng::Blake2s512Rng;
-use crate::rng::FeedableRNG;
-use ark_ff::Field;
+use crate::ml_sumcheck::test_utils::random_instance;
+use crate::ml_sumcheck::{MLSumcheck, Proof, VerificationCertificate};
+use crate::rng::{BatchChallengeSource, Blake2s512Rng};
+use crate::rng::{FeedableRNG, SetupRNG};
+use crate::rng::{ProverTranscript, VerifierTranscript};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::boxed::Box;
 use ark_std::rand::Rng;
-use ark_std::rand::RngCore;
 use ark_std::rc::Rc;
+use ark_std::string::ToString;
 use ark_std::vec::Vec;
 use ark_std::{test_rng, UniformRand};
-use ark_test_curves::bls12_381::Fr;
-
-fn random_product<F: Field, R: RngCore>(
-    nv: usize,
-    num_multiplicands: usize,
-    rng: &mut R,
-) -> (Vec<Rc<DenseMultilinearExtension<F>>>, F) {
-    let mut multiplicands = Vec::with_capacity(num_multiplicands);
-    for _ in 0..num_multiplicands {
-        multiplicands.push(Vec::with_capacity(1 << nv))
-    }
-    let mut sum = F::zero();
-
-    for _ in 0..(1 << nv) {
-        let mut product = F::one();
-        for multiplicand in &mut multiplicands {
-            let val = F::rand(rng);
-            multiplicand.push(val);
-            product *= val;
-        }
-        sum += product;
-    }
-
-    (
-        multiplicands
-            .into_iter()
-            .map(|x| Rc::new(DenseMultilinearExtension::from_evaluations_vec(nv, x)))
-            .collect(),
-        sum,
-    )
-}
-
-fn random_list_of_products<F: Field, R: RngCore>(
-    nv: usize,
-    num_multiplicands_range: (usize, usize),
-    num_products: usize,
-    rng: &mut R,
-) -> (ListOfProductsOfPolynomials<F>, F) {
-    let mut sum = F::zero();
-    let mut poly = ListOfProductsOfPolynomials::new(nv);
-    for _ in 0..num_products {
-        let num_multiplicands = rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
-        let (product, product_sum) = random_product(nv, num_multiplicands, rng);
-        let coefficient = F::rand(rng);
-        poly.add_product(product.into_iter(), coefficient);
-        sum += product_sum * coefficient;
-    }
-
-    (poly, sum)
-}
+use ark_test_curves::bls12_381::{Fq, Fq2, Fr};
 
 fn test_polynomial(nv: usize, num_multiplicands_range: (usize, usize), num_products: usize) {
     let mut rng = test_rng();
     let (poly, asserted_sum) =
-        random_list_of_products::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
+        random_instance::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
     let poly_info = poly.info();
     let proof = MLSumcheck::prove(&poly).expect("fail to prove");
     let subclaim = MLSumcheck::verify(&poly_info, asserted_sum, &proof).expect("fail to verify");
@@ -77,7 +45,7 @@ fn test_polynomial(nv: usize, num_multiplicands_range: (usize, usize), num_produ
 fn test_protocol(nv: usize, num_multiplicands_range: (usize, usize), num_products: usize) {
     let mut rng = test_rng();
     let (poly, asserted_sum) =
-        random_list_of_products::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
+        random_instance::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
     let poly_info = poly.info();
     let mut prover_state = IPForMLSumcheck::prover_init(&poly);
     let mut verifier_state = IPForMLSumcheck::verifier_init(&poly_info);
@@ -105,7 +73,7 @@ fn test_polynomial_as_subprotocol(
 ) {
     let mut rng = test_rng();
     let (poly, asserted_sum) =
-        random_list_of_products::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
+        random_instance::<Fr, _>(nv, num_multiplicands_range, num_products, &mut rng);
     let poly_info = poly.info();
     let (proof, _prover_state) =
         MLSumcheck::prove_as_subprotocol(prover_rng, &poly).expect("fail to prove");
@@ -127,10 +95,10 @@ fn test_multi_degree_subprotocol(
 ) {
     let mut rng = test_rng();
     let (poly_0, asserted_sum_0) =
-        random_list_of_products::<Fr, _>(nv.0, num_multiplicands_range.0, num_products.0, &mut rng);
+        random_instance::<Fr, _>(nv.0, num_multiplicands_range.0, num_products.0, &mut rng);
     let poly_0_info = poly_0.info();
     let (poly_1, asserted_sum_1) =
-        random_list_of_products::<Fr, _>(nv.1, num_multiplicands_range.1, num_products.1, &mut rng);
+        random_instance::<Fr, _>(nv.1, num_multiplicands_range.1, num_products.1, &mut rng);
     let poly_1_info = poly_1.info();
     let (proofs, _prover_states) =
         MLSumcheck::multi_degree_prove_as_subprotocol(prover_rng, &poly_0, &poly_1)
@@ -263,7 +231,7 @@ fn zero_polynomial_protocol_should_error() {
 #[test]
 fn test_extract_sum() {
     let mut rng = test_rng();
-    let (poly, asserted_sum) = random_list_of_products::<Fr, _>(8, (3, 4), 3, &mut rng);
+    let (poly, asserted_sum) = random_instance::<Fr, _>(8, (3, 4), 3, &mut rng);
 
     let proof = MLSumcheck::prove(&poly).expect("fail to prove");
     assert_eq!(MLSumcheck::extract_sum(&proof), asserted_sum);
@@ -324,3 +292,3009 @@ fn test_shared_reference() {
         "wrong subclaim"
     );
 }
+
+#[test]
+fn test_first_divergence() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    assert_eq!(MLSumcheck::first_divergence(&proof, &proof), None);
+
+    let mut tampered = proof.clone();
+    tampered[3].evaluations[1] += Fr::one();
+    assert_eq!(
+        MLSumcheck::first_divergence(&proof, &tampered),
+        Some((3, 1))
+    );
+
+    let truncated = proof[..4].to_vec();
+    assert_eq!(
+        MLSumcheck::first_divergence(&proof, &truncated),
+        Some((4, 0))
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_batch_verify_parallel() {
+    let mut rng = test_rng();
+    let instances: Vec<_> = (0..8)
+        .map(|_| {
+            let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+            let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+            (poly.info(), asserted_sum, proof)
+        })
+        .collect();
+    let mut jobs: Vec<_> = instances
+        .iter()
+        .map(|(info, asserted_sum, proof)| (info.clone(), *asserted_sum, proof))
+        .collect();
+    // make a single job fail
+    jobs[3].1 += Fr::one();
+
+    let results = MLSumcheck::batch_verify_parallel(&jobs);
+    assert_eq!(results.len(), jobs.len());
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result.is_ok(), i != 3);
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_prove_many() {
+    let mut rng = test_rng();
+    let mut instances: Vec<_> = (0..8)
+        .map(|_| random_instance::<Fr, _>(5, (2, 4), 3, &mut rng))
+        .collect();
+    // make a single instance fail
+    instances[5].0 = ListOfProductsOfPolynomials::new(5);
+    let polynomials: Vec<_> = instances.iter().map(|(poly, _)| poly.clone()).collect();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let results = MLSumcheck::prove_many(&polynomials, &pool);
+    assert_eq!(results.len(), instances.len());
+    for (i, (result, (poly, asserted_sum))) in results.iter().zip(&instances).enumerate() {
+        if i == 5 {
+            assert!(matches!(result, Err(crate::Error::EmptyPolynomial)));
+            continue;
+        }
+        let proof = result.as_ref().expect("fail to prove");
+        let expected = MLSumcheck::prove(poly).expect("fail to prove");
+        assert_eq!(
+            MLSumcheck::proof_to_plain_bytes(proof),
+            MLSumcheck::proof_to_plain_bytes(&expected)
+        );
+        MLSumcheck::verify(&poly.info(), *asserted_sum, proof).expect("fail to verify");
+    }
+}
+
+#[test]
+fn test_verify_with_degree_cap() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (3, 4), 2, &mut rng);
+    assert_eq!(poly.max_multiplicands, 3);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let subclaim = MLSumcheck::verify_with_degree_cap(&poly.info(), asserted_sum, &proof, 3)
+        .expect("fail to verify");
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+
+    // the round polynomials have degree 3
+    assert!(MLSumcheck::verify_with_degree_cap(&poly.info(), asserted_sum, &proof, 2).is_err());
+}
+
+#[test]
+fn test_verify_with_newton_interpolation() {
+    let mut rng = test_rng();
+    let newton = VerifyOptions {
+        interpolation: Interpolation::Newton,
+        ..VerifyOptions::default()
+    };
+    for max_multiplicands in 1..7 {
+        let (poly, asserted_sum) =
+            random_instance::<Fr, _>(6, (max_multiplicands, max_multiplicands + 1), 2, &mut rng);
+        let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+        let lagrange =
+            MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+        let subclaim = MLSumcheck::verify_with_options(&poly.info(), asserted_sum, &proof, &newton)
+            .expect("fail to verify");
+        assert_eq!(subclaim.point, lagrange.point);
+        assert_eq!(subclaim.expected_evaluation, lagrange.expected_evaluation);
+        assert!(MLSumcheck::verify_with_options(
+            &poly.info(),
+            asserted_sum + Fr::one(),
+            &proof,
+            &newton
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn test_prove_zero() {
+    let mut rng = test_rng();
+    let (mut poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    assert!(MLSumcheck::prove_zero(&poly).is_err());
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    match MLSumcheck::verify_zero(&poly.info(), &proof) {
+        Err(crate::Error::Reject(Some(reason))) => assert!(reason.starts_with("round 0")),
+        _ => panic!("a nonzero sum should be rejected at round 0"),
+    }
+
+    // subtract the sum as a constant product to make it zero
+    let n = 1 << poly.num_variables;
+    let offset = DenseMultilinearExtension::from_evaluations_vec(
+        poly.num_variables,
+        vec![-asserted_sum / Fr::from(n as u64); n],
+    );
+    poly.add_product(vec![Rc::new(offset)], Fr::one());
+    let proof = MLSumcheck::prove_zero(&poly).expect("fail to prove");
+    let subclaim = MLSumcheck::verify_zero(&poly.info(), &proof).expect("fail to verify");
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+}
+
+#[test]
+fn test_verify_sequence() {
+    let mut rng = test_rng();
+    let (poly_0, sum_0) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (poly_1, sum_1) = random_instance::<Fr, _>(4, (2, 4), 2, &mut rng);
+    let proofs = MLSumcheck::prove_sequence(&[&poly_0, &poly_1]).expect("fail to prove");
+    let mut instances = vec![
+        (poly_0.info(), sum_0, proofs[0].clone()),
+        (poly_1.info(), sum_1, proofs[1].clone()),
+    ];
+    let subclaims = MLSumcheck::verify_sequence(&instances).expect("fail to verify");
+    assert_eq!(subclaims.len(), 2);
+    assert!(poly_0.evaluate(&subclaims[0].point) == subclaims[0].expected_evaluation);
+    assert!(poly_1.evaluate(&subclaims[1].point) == subclaims[1].expected_evaluation);
+
+    // the second proof depends on the first one
+    let standalone = MLSumcheck::prove(&poly_1).expect("fail to prove");
+    assert!(MLSumcheck::verify(&poly_1.info(), sum_1, &proofs[1]).is_err());
+    instances[1].2 = standalone;
+    assert!(MLSumcheck::verify_sequence(&instances).is_err());
+    // and the order matters
+    instances.swap(0, 1);
+    instances[0].2 = proofs[1].clone();
+    instances[1].2 = proofs[0].clone();
+    assert!(MLSumcheck::verify_sequence(&instances).is_err());
+}
+
+#[test]
+fn test_extract_sum_single_evaluation() {
+    // no prover sends a single evaluation: constant polynomials are proved as degree 1
+    let constant = Fr::rand(&mut test_rng());
+    let proof = vec![ProverMsg {
+        evaluations: vec![constant],
+    }];
+    assert!(MLSumcheck::try_extract_sum(&proof).is_err());
+
+    // an empty proof, or an empty first message, has no sum
+    assert!(MLSumcheck::<Fr>::try_extract_sum(&Vec::new()).is_err());
+    let empty_message = vec![ProverMsg::<Fr> {
+        evaluations: Vec::new(),
+    }];
+    assert!(MLSumcheck::try_extract_sum(&empty_message).is_err());
+}
+
+#[test]
+fn test_polynomial_info_encoding() {
+    let info = PolynomialInfo {
+        max_multiplicands: 3,
+        num_variables: 0x0102,
+    };
+    let mut bytes = Vec::new();
+    info.serialize_uncompressed(&mut bytes).unwrap();
+    assert_eq!(
+        bytes,
+        vec![3, 0, 0, 0, 0, 0, 0, 0, 0x02, 0x01, 0, 0, 0, 0, 0, 0]
+    );
+    let mut compressed = Vec::new();
+    info.serialize_compressed(&mut compressed).unwrap();
+    assert_eq!(compressed, bytes);
+    assert_eq!(info.uncompressed_size(), PolynomialInfo::ENCODED_SIZE);
+
+    let decoded = PolynomialInfo::deserialize_uncompressed(&bytes[..]).unwrap();
+    assert_eq!(decoded.max_multiplicands, info.max_multiplicands);
+    assert_eq!(decoded.num_variables, info.num_variables);
+}
+
+#[test]
+fn test_shape() {
+    let mut rng = test_rng();
+    let mut poly = ListOfProductsOfPolynomials::new(5);
+    assert_eq!(poly.shape(), (5, 0, 0));
+    let (product, _) = random_product::<Fr, _>(5, 3, &mut rng);
+    poly.add_product(product, Fr::rand(&mut rng));
+    let (product, _) = random_product::<Fr, _>(5, 2, &mut rng);
+    poly.add_product(product, Fr::rand(&mut rng));
+    assert_eq!(poly.shape(), (5, 3, 2));
+}
+
+fn run_equality_check(
+    f: &ListOfProductsOfPolynomials<Fr>,
+    g: &ListOfProductsOfPolynomials<Fr>,
+) -> Result<(), crate::Error> {
+    let mut prover_rng = Blake2s512Rng::setup();
+    let proof = EqualityCheck::prove(f, g, &mut prover_rng).expect("fail to prove");
+    let mut verifier_rng = Blake2s512Rng::setup();
+    EqualityCheck::verify(f, g, &proof, &mut verifier_rng)
+}
+
+#[test]
+fn test_equality_check() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let (f, _) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+
+    // same polynomial with the products in a different order
+    let mut g = ListOfProductsOfPolynomials::new(nv);
+    for (coefficient, product) in f.products.iter().rev() {
+        g.add_product(
+            product
+                .iter()
+                .map(|&i| f.flattened_ml_extensions[i].clone()),
+            *coefficient,
+        );
+    }
+    run_equality_check(&f, &g).expect("equal polynomials should pass");
+
+    let (h, _) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    assert!(run_equality_check(&f, &h).is_err());
+}
+
+#[test]
+fn test_equality_check_single_point_mismatch() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let a = Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+    let b = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let mut tampered_evaluations = b.evaluations.clone();
+    tampered_evaluations[5] += Fr::one();
+    let b_tampered = DenseMultilinearExtension::from_evaluations_vec(nv, tampered_evaluations);
+
+    let mut f = ListOfProductsOfPolynomials::new(nv);
+    f.add_product(vec![a.clone(), Rc::new(b)], Fr::one());
+    let mut g = ListOfProductsOfPolynomials::new(nv);
+    g.add_product(vec![a, Rc::new(b_tampered)], Fr::one());
+    assert!(run_equality_check(&f, &g).is_err());
+}
+
+#[test]
+fn test_cached_round_zero() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(8, (2, 5), 4, &mut rng);
+    let round_zero = IPForMLSumcheck::prover_init(&poly).precompute_round_zero();
+
+    for context in [&b"context 1"[..], &b"context 2"[..]] {
+        let mut rng_uncached = Blake2s512Rng::setup();
+        rng_uncached.feed(&context.to_vec()).unwrap();
+        let (uncached, _) =
+            MLSumcheck::prove_as_subprotocol(&mut rng_uncached, &poly).expect("fail to prove");
+
+        let mut rng_cached = Blake2s512Rng::setup();
+        rng_cached.feed(&context.to_vec()).unwrap();
+        let (cached, _) =
+            MLSumcheck::prove_as_subprotocol_with_round_zero(&mut rng_cached, &poly, &round_zero)
+                .expect("fail to prove");
+
+        assert_eq!(MLSumcheck::first_divergence(&uncached, &cached), None);
+    }
+}
+
+#[test]
+fn test_borrowed_prover() {
+    let mut rng = test_rng();
+    let nv = 8;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 5), 4, &mut rng);
+    let mut owned_state = IPForMLSumcheck::prover_init(&poly);
+    let mut borrowed_state = IPForMLSumcheck::prover_init_borrowed(&poly);
+    // the borrowing prover does not copy any table upfront
+    assert!(borrowed_state.folded_state().is_none());
+
+    let mut verifier_state = IPForMLSumcheck::verifier_init(&poly.info());
+    let mut verifier_msg = None;
+    for round in 0..nv {
+        let owned_msg = IPForMLSumcheck::prove_round(&mut owned_state, &verifier_msg);
+        let borrowed_msg =
+            IPForMLSumcheck::prove_round_borrowed(&mut borrowed_state, &verifier_msg);
+        assert_eq!(owned_msg.evaluations, borrowed_msg.evaluations);
+        if round > 0 {
+            // after the first fold, the workspace has tables of half the original size
+            let folded = borrowed_state.folded_state().unwrap();
+            assert_eq!(
+                folded.flattened_ml_extensions.len(),
+                poly.flattened_ml_extensions.len()
+            );
+            assert_eq!(
+                folded.flattened_ml_extensions[0].evaluations.len(),
+                1 << (nv - round)
+            );
+        }
+        verifier_msg = IPForMLSumcheck::verify_round(borrowed_msg, &mut verifier_state, &mut rng);
+    }
+    let subclaim = IPForMLSumcheck::check_and_generate_subclaim(verifier_state, asserted_sum)
+        .expect("fail to generate subclaim");
+    assert!(
+        poly.evaluate(&subclaim.point) == subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+#[test]
+fn test_padded_proof() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(10, (2, 4), 3, &mut rng);
+    let poly_info = poly.info();
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let padded = MLSumcheck::pad_to(&proof, 16).expect("fail to pad");
+    assert_eq!(padded.len(), 16);
+
+    let subclaim =
+        MLSumcheck::verify_padded(&poly_info, asserted_sum, &padded).expect("fail to verify");
+    assert!(
+        poly.evaluate(&subclaim.point) == subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+
+    let mut tampered = padded.clone();
+    tampered[12].evaluations[0] = Fr::one();
+    assert!(MLSumcheck::verify_padded(&poly_info, asserted_sum, &tampered).is_err());
+    assert!(matches!(
+        MLSumcheck::verify_padded(&poly_info, asserted_sum, &proof[..9].to_vec()),
+        Err(crate::Error::WrongProofLength {
+            expected: 10,
+            got: 9
+        })
+    ));
+    assert!(matches!(
+        MLSumcheck::pad_to(&proof, 9),
+        Err(crate::Error::WrongProofLength {
+            expected: 9,
+            got: 10
+        })
+    ));
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn test_verify_counting() {
+    let mut rng = test_rng();
+    let nv = 4;
+    // exactly 3 multiplicands, so each round message has 4 evaluations
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (3, 4), 2, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let (subclaim, stats) =
+        MLSumcheck::verify_counting(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+
+    // with 4 evaluations per round:
+    // - 3 multiplications for the factorials,
+    // - per round, 4 * (1 addition + 2 multiplications) for the denominators,
+    // - 3 * 16 + 1 multiplications and a single inversion for the batch inversion of the 16 denominators,
+    // - per round, 4 additions and 9 multiplications for the barycentric formula, and 1 addition for the round check.
+    assert_eq!(stats.additions, nv * (4 + 4 + 1));
+    assert_eq!(stats.multiplications, 3 + nv * 8 + (3 * 16 + 1) + nv * 9);
+    assert_eq!(stats.inversions, 1);
+}
+
+#[test]
+fn test_forked_transcript_for_nested_protocol() {
+    let mut rng = test_rng();
+    let (outer_poly, outer_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (inner_poly, inner_sum) = random_instance::<Fr, _>(4, (2, 4), 2, &mut rng);
+
+    let mut outer_rng = Blake2s512Rng::setup();
+    outer_rng.feed(b"outer protocol").unwrap();
+    let mut inner_rng = outer_rng.fork();
+    let (inner_proof, _) =
+        MLSumcheck::prove_as_subprotocol(&mut inner_rng, &inner_poly).expect("fail to prove");
+    let (outer_proof, _) =
+        MLSumcheck::prove_as_subprotocol(&mut outer_rng, &outer_poly).expect("fail to prove");
+
+    // the outer transcript is not affected by the feeds of the inner protocol
+    let mut reference_rng = Blake2s512Rng::setup();
+    reference_rng.feed(b"outer protocol").unwrap();
+    let (reference_proof, _) =
+        MLSumcheck::prove_as_subprotocol(&mut reference_rng, &outer_poly).expect("fail to prove");
+    assert_eq!(
+        MLSumcheck::first_divergence(&outer_proof, &reference_proof),
+        None
+    );
+    assert_eq!(Fr::rand(&mut outer_rng), Fr::rand(&mut reference_rng));
+
+    // the verifier forks at the same point
+    let mut outer_rng = Blake2s512Rng::setup();
+    outer_rng.feed(b"outer protocol").unwrap();
+    let mut inner_rng = outer_rng.fork();
+    let inner_subclaim = MLSumcheck::verify_as_subprotocol(
+        &mut inner_rng,
+        &inner_poly.info(),
+        inner_sum,
+        &inner_proof,
+    )
+    .expect("fail to verify");
+    let outer_subclaim = MLSumcheck::verify_as_subprotocol(
+        &mut outer_rng,
+        &outer_poly.info(),
+        outer_sum,
+        &outer_proof,
+    )
+    .expect("fail to verify");
+    assert!(inner_poly.evaluate(&inner_subclaim.point) == inner_subclaim.expected_evaluation);
+    assert!(outer_poly.evaluate(&outer_subclaim.point) == outer_subclaim.expected_evaluation);
+}
+
+#[test]
+fn test_prove_over_scaled_domain() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let (poly, _) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let shift = Fr::rand(&mut rng);
+
+    // sum over {0, shift}^nv computed directly
+    let mut expected_sum = Fr::zero();
+    for x in 0..(1 << nv) {
+        let point: Vec<_> = (0..nv)
+            .map(|i| if (x >> i) & 1 == 1 { shift } else { Fr::zero() })
+            .collect();
+        expected_sum += poly.evaluate(&point);
+    }
+
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (proof, _) =
+        MLSumcheck::prove_over_domain(&mut prover_rng, &poly, shift).expect("fail to prove");
+    assert_eq!(MLSumcheck::extract_sum(&proof), expected_sum);
+
+    let mut verifier_rng = Blake2s512Rng::setup();
+    let subclaim = MLSumcheck::verify_over_domain(
+        &mut verifier_rng,
+        &poly.info(),
+        shift,
+        expected_sum,
+        &proof,
+    )
+    .expect("fail to verify");
+    assert!(
+        poly.evaluate(&subclaim.point) == subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+#[test]
+fn test_prover_challenges() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(7, (2, 4), 3, &mut rng);
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (proof, prover_state) =
+        MLSumcheck::prove_as_subprotocol(&mut prover_rng, &poly).expect("fail to prove");
+    assert_eq!(prover_state.challenges().len(), poly.num_variables);
+
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    assert_eq!(prover_state.challenges(), &subclaim.point[..]);
+}
+
+#[test]
+fn test_prove_round_into() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(6, (2, 5), 4, &mut rng);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut reused_state = IPForMLSumcheck::prover_init(&poly);
+    let mut buf = Vec::new();
+    let mut verifier_msg = None;
+    for _ in 0..poly.num_variables {
+        let prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        IPForMLSumcheck::prove_round_into(&mut reused_state, &verifier_msg, &mut buf);
+        assert_eq!(prover_msg.evaluations, buf);
+        verifier_msg = Some(IPForMLSumcheck::sample_round(&mut rng));
+    }
+}
+
+#[test]
+fn test_empty_polynomial_should_error() {
+    let poly = ListOfProductsOfPolynomials::<Fr>::new(5);
+    assert_eq!(poly.num_products(), 0);
+    assert!(matches!(
+        MLSumcheck::prove(&poly),
+        Err(crate::Error::EmptyPolynomial)
+    ));
+}
+
+#[test]
+fn test_add_power() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let coefficient = Fr::rand(&mut rng);
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_power(coefficient, &f, 3);
+    assert_eq!(poly.flattened_ml_extensions.len(), 1);
+    assert_eq!(poly.max_multiplicands, 3);
+
+    let asserted_sum = coefficient * f.evaluations.iter().map(|x| *x * x * x).sum::<Fr>();
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    assert!(
+        poly.evaluate(&subclaim.point) == subclaim.expected_evaluation,
+        "wrong subclaim"
+    );
+}
+
+#[cfg(feature = "prover-self-check")]
+#[test]
+#[should_panic(expected = "prover self-check failed")]
+fn test_prover_self_check_catches_corrupted_fold() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_state = IPForMLSumcheck::verifier_init(&poly.info());
+    let prover_msg = IPForMLSumcheck::prove_round(&mut prover_state, &None);
+    let verifier_msg = IPForMLSumcheck::verify_round(prover_msg, &mut verifier_state, &mut rng);
+    // tamper with a table so that the next fold is wrong
+    prover_state.flattened_ml_extensions[0].evaluations[0] += Fr::one();
+    IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+}
+
+#[test]
+fn test_multi_degree_verify_separately() {
+    let mut rng = test_rng();
+    let (poly_0, asserted_sum_0) = random_instance::<Fr, _>(7, (3, 5), 4, &mut rng);
+    let (poly_1, asserted_sum_1) = random_instance::<Fr, _>(4, (2, 3), 3, &mut rng);
+    let (proofs, _) = MLSumcheck::multi_degree_prove_as_subprotocol(
+        &mut Blake2s512Rng::setup(),
+        &poly_0,
+        &poly_1,
+    )
+    .expect("fail to prove");
+    let (subclaim_0, subclaim_1) = MLSumcheck::multi_degree_verify_separately(
+        &mut Blake2s512Rng::setup(),
+        (&poly_0.info(), &poly_1.info()),
+        (asserted_sum_0, asserted_sum_1),
+        (&proofs.0, &proofs.1),
+    )
+    .expect("fail to verify");
+    assert_eq!(
+        poly_0.evaluate(&subclaim_0.point),
+        subclaim_0.expected_evaluation
+    );
+    assert_eq!(
+        poly_1.evaluate(&subclaim_1.point),
+        subclaim_1.expected_evaluation
+    );
+    assert_eq!(
+        subclaim_1.point[..],
+        subclaim_0.point[..poly_1.num_variables]
+    );
+
+    // the challenges of a standalone transcript do not match the shared one
+    assert!(MLSumcheck::verify(&poly_0.info(), asserted_sum_0, &proofs.0).is_err());
+}
+
+#[test]
+fn test_prover_claimed_sum() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(7, (2, 5), 4, &mut rng);
+    let prover_state = IPForMLSumcheck::prover_init(&poly);
+    let claimed_sum = prover_state.claimed_sum();
+    assert_eq!(claimed_sum, asserted_sum);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    assert_eq!(claimed_sum, MLSumcheck::extract_sum(&proof));
+}
+
+#[test]
+fn test_typed_transcripts() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let mut prover_transcript = ProverTranscript::<Blake2s512Rng>::setup();
+    let (proof, _) =
+        MLSumcheck::prove_with_transcript(&mut prover_transcript, &poly).expect("fail to prove");
+    let mut verifier_transcript = VerifierTranscript::<Blake2s512Rng>::setup();
+    let subclaim = MLSumcheck::verify_with_transcript(
+        &mut verifier_transcript,
+        &poly.info(),
+        asserted_sum,
+        &proof,
+    )
+    .expect("fail to verify");
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+
+    // both transcripts end in the same state
+    assert_eq!(
+        Fr::rand(prover_transcript.as_rng_mut()),
+        Fr::rand(verifier_transcript.as_rng_mut())
+    );
+}
+
+#[test]
+fn test_transcript_bytes() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(3, (2, 3), 2, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let bytes = MLSumcheck::transcript_bytes(&poly.info(), &proof);
+    let round_size = 8 + (poly.max_multiplicands + 1) * 32;
+    assert_eq!(bytes.len(), 16 + 3 * round_size);
+    // info: max_multiplicands and num_variables, then the length of the first message
+    let mut prefix = vec![0u8; 24];
+    prefix[0] = poly.max_multiplicands as u8;
+    prefix[8] = 3;
+    prefix[16] = (poly.max_multiplicands + 1) as u8;
+    assert_eq!(bytes[..24], prefix[..]);
+
+    // feeding the same bytes reproduces the verifier challenges
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    let mut fs_rng = Blake2s512Rng::setup();
+    for b in &bytes[..16] {
+        fs_rng.feed(b).unwrap();
+    }
+    for (round, r) in bytes[16..].chunks(round_size).zip(&subclaim.point) {
+        for b in round {
+            fs_rng.feed(b).unwrap();
+        }
+        assert_eq!(Fr::rand(&mut fs_rng), *r);
+    }
+}
+
+#[test]
+fn test_prove_rlc() {
+    let mut rng = test_rng();
+    let constraints: Vec<_> = (0..3)
+        .map(|k| random_instance::<Fr, _>(5, (2, 3 + k), 2, &mut rng))
+        .collect();
+    let polynomials: Vec<_> = constraints.iter().map(|(p, _)| p).collect();
+    let infos: Vec<_> = polynomials.iter().map(|p| p.info()).collect();
+    let sums: Vec<_> = constraints.iter().map(|(_, s)| *s).collect();
+
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (proof, rho) = MLSumcheck::prove_rlc(&polynomials, &mut prover_rng).expect("fail to prove");
+    let mut verifier_rng = Blake2s512Rng::setup();
+    let (subclaim, verifier_rho) =
+        MLSumcheck::verify_rlc(&infos, &sums, &proof, &mut verifier_rng).expect("fail to verify");
+    assert_eq!(rho, verifier_rho);
+    let expected = polynomials
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, p| acc * rho + p.evaluate(&subclaim.point));
+    assert_eq!(expected, subclaim.expected_evaluation);
+
+    // a wrong sum for one constraint is detected
+    let mut wrong_sums = sums.clone();
+    wrong_sums[1] += Fr::one();
+    let mut verifier_rng = Blake2s512Rng::setup();
+    assert!(MLSumcheck::verify_rlc(&infos, &wrong_sums, &proof, &mut verifier_rng).is_err());
+}
+
+#[test]
+fn test_prove_partial_sum() {
+    let mut rng = test_rng();
+    let (nv, k) = (7, 3);
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (g, proof) = MLSumcheck::prove_partial_sum(&f, k, &mut prover_rng).expect("fail to prove");
+    assert_eq!(g.num_vars, nv - k);
+    assert_eq!(
+        g.evaluations.iter().sum::<Fr>(),
+        f.evaluations.iter().sum::<Fr>()
+    );
+
+    let mut verifier_rng = Blake2s512Rng::setup();
+    let subclaim =
+        MLSumcheck::verify_partial_sum(&g, k, &proof, &mut verifier_rng).expect("fail to verify");
+    assert_eq!(subclaim.point.len(), nv);
+    assert_eq!(
+        f.evaluate(&subclaim.point).unwrap(),
+        subclaim.expected_evaluation
+    );
+
+    // a wrong partial sum is rejected
+    let mut wrong = g.clone();
+    wrong.evaluations[0] += Fr::one();
+    let mut verifier_rng = Blake2s512Rng::setup();
+    assert!(MLSumcheck::verify_partial_sum(&wrong, k, &proof, &mut verifier_rng).is_err());
+}
+
+#[test]
+fn test_prove_masked_sum() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let selector: Vec<Fr> = (0..1 << nv)
+        .map(|_| if rng.gen() { Fr::one() } else { Fr::zero() })
+        .collect();
+    let s = DenseMultilinearExtension::from_evaluations_vec(nv, selector);
+    let masked_sum: Fr = f
+        .evaluations
+        .iter()
+        .zip(&s.evaluations)
+        .filter(|(_, s_x)| s_x.is_one())
+        .map(|(f_x, _)| *f_x)
+        .sum();
+
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (sum_proof, booleanity_proof) =
+        MLSumcheck::prove_masked_sum(&f, &s, &mut prover_rng).expect("fail to prove");
+    let mut verifier_rng = Blake2s512Rng::setup();
+    MLSumcheck::verify_masked_sum(
+        &f,
+        &s,
+        masked_sum,
+        (&sum_proof, &booleanity_proof),
+        &mut verifier_rng,
+    )
+    .expect("fail to verify");
+
+    // a selector taking the value 2 passes the sumcheck but fails the booleanity check
+    let mut not_boolean = s.clone();
+    not_boolean.evaluations[0] = Fr::from(2u64);
+    let sum = masked_sum + (Fr::from(2u64) - s.evaluations[0]) * f.evaluations[0];
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (sum_proof, booleanity_proof) =
+        MLSumcheck::prove_masked_sum(&f, &not_boolean, &mut prover_rng).expect("fail to prove");
+    assert_eq!(MLSumcheck::extract_sum(&sum_proof), sum);
+    let mut verifier_rng = Blake2s512Rng::setup();
+    assert!(MLSumcheck::verify_masked_sum(
+        &f,
+        &not_boolean,
+        sum,
+        (&sum_proof, &booleanity_proof),
+        &mut verifier_rng,
+    )
+    .is_err());
+}
+
+#[test]
+fn test_proof_plain_bytes() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let bytes = MLSumcheck::proof_to_plain_bytes(&proof);
+    assert_eq!(bytes.len(), 5 * (poly.max_multiplicands + 1) * 32);
+
+    let parsed =
+        MLSumcheck::<Fr>::proof_from_plain_bytes(&bytes, &poly.info()).expect("fail to parse");
+    assert_eq!(MLSumcheck::first_divergence(&proof, &parsed), None);
+    MLSumcheck::verify(&poly.info(), asserted_sum, &parsed).expect("fail to verify");
+
+    // wrong length
+    assert!(MLSumcheck::<Fr>::proof_from_plain_bytes(&bytes[1..], &poly.info()).is_err());
+    // an element which is not smaller than the modulus
+    let mut non_canonical = bytes;
+    non_canonical[..32].copy_from_slice(&[0xff; 32]);
+    assert!(MLSumcheck::<Fr>::proof_from_plain_bytes(&non_canonical, &poly.info()).is_err());
+}
+
+#[test]
+fn test_incremental_verifier() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+
+    let mut verifier = IncrementalVerifier::new(&poly.info(), asserted_sum);
+    for (i, msg) in proof.iter().enumerate() {
+        let progress = verifier
+            .feed_round(msg.clone())
+            .expect("fail to feed round");
+        assert_eq!(progress.rounds_done, i + 1);
+        assert_eq!(progress.num_variables, nv);
+        assert_eq!(progress.is_complete(), i + 1 == nv);
+    }
+    assert!(verifier.feed_round(proof[0].clone()).is_err());
+    let subclaim = verifier.finish().expect("fail to verify");
+    let expected = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    assert_eq!(subclaim.point, expected.point);
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+
+    let mut incomplete = IncrementalVerifier::new(&poly.info(), asserted_sum);
+    incomplete
+        .feed_round(proof[0].clone())
+        .expect("fail to feed round");
+    assert!(matches!(
+        incomplete.finish(),
+        Err(crate::Error::WrongProofLength {
+            expected: 6,
+            got: 1
+        })
+    ));
+}
+
+#[test]
+fn test_malformed_input_does_not_panic() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(4, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let sizes = [0, 1, 2, 3, 4, 5, usize::MAX];
+    for _ in 0..300 {
+        let info = PolynomialInfo {
+            max_multiplicands: sizes[rng.gen_range(0..sizes.len())],
+            num_variables: sizes[rng.gen_range(0..sizes.len())],
+        };
+        let malformed: Proof<Fr> = (0..rng.gen_range(0..6))
+            .map(|_| ProverMsg {
+                evaluations: (0..rng.gen_range(0..6))
+                    .map(|_| Fr::rand(&mut rng))
+                    .collect(),
+            })
+            .collect();
+        let truncated: Proof<Fr> = proof[..rng.gen_range(0..proof.len())].to_vec();
+        for candidate in [&malformed, &truncated, &proof] {
+            let _ = MLSumcheck::verify(&info, asserted_sum, candidate);
+            let _ = MLSumcheck::verify(&poly.info(), asserted_sum, candidate);
+            let _ = MLSumcheck::verify_padded(&info, asserted_sum, candidate);
+            let _ = MLSumcheck::multi_degree_verify_as_subprotocol(
+                &mut Blake2s512Rng::setup(),
+                (&info, &poly.info()),
+                asserted_sum,
+                (candidate, &proof),
+            );
+            let bytes = MLSumcheck::proof_to_plain_bytes(candidate);
+            let _ = MLSumcheck::<Fr>::proof_from_plain_bytes(&bytes, &info);
+        }
+    }
+
+    let constant = ListOfProductsOfPolynomials::<Fr>::new(0);
+    assert!(matches!(
+        MLSumcheck::prove(&constant),
+        Err(crate::Error::ConstantPolynomial)
+    ));
+
+    // the fields of the polynomial are public, so it can be malformed after being built
+    let mut missing_multiplicand = poly.clone();
+    missing_multiplicand.products[0].1.push(99);
+    let mut short_table = poly.clone();
+    short_table.flattened_ml_extensions[0] = Rc::new(DenseMultilinearExtension {
+        evaluations: vec![Fr::one(); 3],
+        num_vars: 4,
+    });
+    let mut wrong_num_vars = poly.clone();
+    wrong_num_vars.flattened_ml_extensions[0] =
+        Rc::new(DenseMultilinearExtension::rand(3, &mut rng));
+    let mut low_degree = poly.clone();
+    low_degree.max_multiplicands = 1;
+    for malformed in [
+        &missing_multiplicand,
+        &short_table,
+        &wrong_num_vars,
+        &low_degree,
+    ] {
+        assert!(matches!(
+            MLSumcheck::prove(malformed),
+            Err(crate::Error::OtherError(_))
+        ));
+        assert!(MLSumcheck::prove_with_digest(malformed).is_err());
+        assert!(MLSumcheck::prove_rlc(&[&poly, malformed], &mut Blake2s512Rng::setup()).is_err());
+        assert!(SumcheckProver::new(malformed).is_err());
+        assert!(IPForMLSumcheck::try_prover_init_borrowed(malformed).is_err());
+        assert!(MLSumcheck::prove_even(0, malformed).is_err());
+        assert!(MLSumcheck::prove_with_permutation(
+            malformed,
+            &[1, 0, 2, 3],
+            &mut Blake2s512Rng::setup()
+        )
+        .is_err());
+        assert!(MLSumcheck::prove_over_domain(
+            &mut Blake2s512Rng::setup(),
+            malformed,
+            Fr::from(3u64)
+        )
+        .is_err());
+    }
+    assert!(matches!(
+        IPForMLSumcheck::try_prover_init_borrowed(&constant),
+        Err(crate::Error::ConstantPolynomial)
+    ));
+
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_rng = Blake2s512Rng::setup();
+    assert!(IPForMLSumcheck::try_prove_round(
+        &mut prover_state,
+        &Some(IPForMLSumcheck::sample_round(&mut verifier_rng))
+    )
+    .is_err());
+    let mut verifier_msg = None;
+    for _ in 0..poly.num_variables {
+        IPForMLSumcheck::try_prove_round(&mut prover_state, &verifier_msg).unwrap();
+        assert!(IPForMLSumcheck::try_prove_round(&mut prover_state, &None).is_err());
+        verifier_msg = Some(IPForMLSumcheck::try_sample_round(&mut verifier_rng).unwrap());
+    }
+    assert!(matches!(
+        IPForMLSumcheck::try_prove_round(&mut prover_state, &verifier_msg),
+        Err(crate::Error::ProtocolFinished)
+    ));
+
+    // the same misuses of a borrowing prover
+    let unstarted = IPForMLSumcheck::try_prover_init_borrowed(&poly).unwrap();
+    assert!(unstarted.try_into_prover_state().is_err());
+    let mut borrowed_state = IPForMLSumcheck::try_prover_init_borrowed(&poly).unwrap();
+    let mut verifier_rng = Blake2s512Rng::setup();
+    assert!(IPForMLSumcheck::try_prove_round_borrowed(
+        &mut borrowed_state,
+        &Some(IPForMLSumcheck::sample_round(&mut verifier_rng))
+    )
+    .is_err());
+    let mut verifier_msg = None;
+    for _ in 0..poly.num_variables {
+        IPForMLSumcheck::try_prove_round_borrowed(&mut borrowed_state, &verifier_msg).unwrap();
+        assert!(IPForMLSumcheck::try_prove_round_borrowed(&mut borrowed_state, &None).is_err());
+        verifier_msg = Some(IPForMLSumcheck::sample_round(&mut verifier_rng));
+    }
+    assert!(matches!(
+        IPForMLSumcheck::try_prove_round_borrowed(&mut borrowed_state, &verifier_msg),
+        Err(crate::Error::ProtocolFinished)
+    ));
+    assert!(borrowed_state.try_into_prover_state().is_ok());
+    // with one variable, the first fold is past the last round
+    let mut single_variable = ListOfProductsOfPolynomials::new(1);
+    single_variable.add_product(
+        vec![Rc::new(DenseMultilinearExtension::<Fr>::rand(1, &mut rng))],
+        Fr::one(),
+    );
+    let mut borrowed_state = IPForMLSumcheck::try_prover_init_borrowed(&single_variable).unwrap();
+    IPForMLSumcheck::try_prove_round_borrowed(&mut borrowed_state, &None).unwrap();
+    assert!(matches!(
+        IPForMLSumcheck::try_prove_round_borrowed(
+            &mut borrowed_state,
+            &Some(IPForMLSumcheck::sample_round(&mut Blake2s512Rng::setup()))
+        ),
+        Err(crate::Error::ProtocolFinished)
+    ));
+
+    // malformed oracles
+    let products = vec![(Fr::one(), vec![0, 1])];
+    for num_vars in [vec![], vec![3, 4], vec![0, 0], vec![3], vec![3, 3]] {
+        let oracles: Vec<_> = num_vars
+            .iter()
+            .map(|&nv| DenseMultilinearExtension::<Fr>::rand(nv, &mut rng))
+            .collect();
+        assert_eq!(
+            IPForMLSumcheck::try_prover_init_from_oracles(products.clone(), oracles).is_ok(),
+            num_vars == [3, 3]
+        );
+    }
+
+    let mut checked_rng = Blake2s512Rng::setup();
+    let mut unchecked_rng = Blake2s512Rng::setup();
+    assert_eq!(
+        IPForMLSumcheck::<Fr>::try_sample_round(&mut checked_rng)
+            .unwrap()
+            .randomness,
+        IPForMLSumcheck::<Fr>::sample_round(&mut unchecked_rng).randomness
+    );
+}
+
+#[test]
+fn test_add_product_padded() {
+    let mut rng = test_rng();
+    let coefficient = Fr::rand(&mut rng);
+    let tables: Vec<Vec<Fr>> = (0..2)
+        .map(|_| (0..5).map(|_| Fr::rand(&mut rng)).collect())
+        .collect();
+    let mut padded = ListOfProductsOfPolynomials::new(3);
+    padded.add_product_padded(coefficient, tables.clone());
+
+    let mut extended = ListOfProductsOfPolynomials::new(3);
+    extended.add_product(
+        tables.iter().map(|table| {
+            let mut evaluations = table.clone();
+            evaluations.resize(8, Fr::zero());
+            Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+                3,
+                evaluations,
+            ))
+        }),
+        coefficient,
+    );
+
+    let sum = coefficient * (0..5).map(|i| tables[0][i] * tables[1][i]).sum::<Fr>();
+    let proof = MLSumcheck::prove(&padded).expect("fail to prove");
+    assert_eq!(MLSumcheck::extract_sum(&proof), sum);
+    assert_eq!(
+        MLSumcheck::first_divergence(
+            &proof,
+            &MLSumcheck::prove(&extended).expect("fail to prove")
+        ),
+        None
+    );
+    MLSumcheck::verify(&padded.info(), sum, &proof).expect("fail to verify");
+
+    // 9 rows pad to 4 variables, and 4 rows to 2 variables
+    for rows in [4, 9] {
+        let table = (0..rows).map(|_| Fr::rand(&mut rng)).collect();
+        assert!(padded
+            .try_add_product_padded(coefficient, vec![tables[0].clone(), table])
+            .is_err());
+    }
+    assert_eq!(padded.products.len(), 1);
+    let mut too_many_variables = ListOfProductsOfPolynomials::<Fr>::new(usize::BITS as usize);
+    assert!(too_many_variables
+        .try_add_product_padded(coefficient, vec![Vec::new()])
+        .is_err());
+}
+
+#[test]
+fn test_prove_even_and_odd() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    for var_index in [0, 2, nv - 1] {
+        let even = MLSumcheck::prove_even(var_index, &poly).expect("fail to prove");
+        let odd = MLSumcheck::prove_odd(var_index, &poly).expect("fail to prove");
+        let (even_sum, odd_sum) = (
+            MLSumcheck::extract_sum(&even),
+            MLSumcheck::extract_sum(&odd),
+        );
+        assert_eq!(even_sum + odd_sum, asserted_sum);
+
+        let subclaim = MLSumcheck::verify_even(var_index, &poly.info(), even_sum, &even)
+            .expect("fail to verify");
+        assert!(subclaim.point[var_index].is_zero());
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+        let subclaim =
+            MLSumcheck::verify_odd(var_index, &poly.info(), odd_sum, &odd).expect("fail to verify");
+        assert!(subclaim.point[var_index].is_one());
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    }
+    assert!(MLSumcheck::prove_even(nv, &poly).is_err());
+}
+
+#[test]
+fn test_verify_collect_expected() {
+    let mut rng = test_rng();
+    let nv = 7;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 5), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let expected = MLSumcheck::verify_collect_expected(&poly.info(), asserted_sum, &proof)
+        .expect("fail to verify");
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    assert_eq!(expected.len(), nv);
+    assert_eq!(expected[nv - 1], subclaim.expected_evaluation);
+    for (sum, msg) in expected.iter().zip(proof.iter().skip(1)) {
+        assert_eq!(*sum, msg.evaluations[0] + msg.evaluations[1]);
+    }
+    assert!(
+        MLSumcheck::verify_collect_expected(&poly.info(), asserted_sum + Fr::one(), &proof)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_prove_tensor_product() {
+    let mut rng = test_rng();
+    for (n, m) in [(3, 2), (1, 4), (0, 3), (4, 0)] {
+        let tensor = TensorProduct::new(
+            DenseMultilinearExtension::<Fr>::rand(n, &mut rng),
+            DenseMultilinearExtension::<Fr>::rand(m, &mut rng),
+        );
+        let (proof, challenges) =
+            MLSumcheck::prove_tensor_product(&mut Blake2s512Rng::setup(), &tensor)
+                .expect("fail to prove");
+
+        let mut materialized = ListOfProductsOfPolynomials::new(n + m);
+        materialized.add_product(vec![Rc::new(tensor.materialize())], Fr::one());
+        let expected = MLSumcheck::prove(&materialized).expect("fail to prove");
+        assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+
+        let asserted_sum = tensor.materialize().evaluations.iter().sum();
+        let subclaim =
+            MLSumcheck::verify(&tensor.info(), asserted_sum, &proof).expect("fail to verify");
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(
+            tensor.evaluate(&subclaim.point),
+            subclaim.expected_evaluation
+        );
+    }
+}
+
+#[test]
+fn test_challenges_cannot_be_precomputed() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+
+    // sample every challenge before computing any message
+    let mut fs_rng = Blake2s512Rng::setup();
+    fs_rng.feed(&poly.info()).unwrap();
+    let challenges: Vec<_> = (0..nv)
+        .map(|_| IPForMLSumcheck::<Fr>::sample_round(&mut fs_rng))
+        .collect();
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_msg = None;
+    let mut proof = Vec::with_capacity(nv);
+    for challenge in challenges {
+        proof.push(IPForMLSumcheck::prove_round(
+            &mut prover_state,
+            &verifier_msg,
+        ));
+        verifier_msg = Some(challenge);
+    }
+
+    assert!(MLSumcheck::verify(&poly.info(), asserted_sum, &proof).is_err());
+}
+
+#[test]
+fn test_final_multiplicand_evals() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(6, (2, 5), 4, &mut rng);
+    let (proof, prover_state) =
+        MLSumcheck::prove_as_subprotocol(&mut Blake2s512Rng::setup(), &poly)
+            .expect("fail to prove");
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+
+    let evals = prover_state.final_multiplicand_evals();
+    assert_eq!(evals.len(), poly.products.len());
+    let mut expected = Fr::zero();
+    for ((coefficient, product), product_evals) in poly.products.iter().zip(evals.iter()) {
+        assert_eq!(product.len(), product_evals.len());
+        for (&i, eval) in product.iter().zip(product_evals.iter()) {
+            assert_eq!(
+                poly.flattened_ml_extensions[i].evaluate(&subclaim.point),
+                Some(*eval)
+            );
+        }
+        expected += *coefficient * product_evals.iter().product::<Fr>();
+    }
+    assert_eq!(expected, subclaim.expected_evaluation);
+}
+
+/// Mock commitment scheme: the commitment is a hash of the evaluations.
+struct HashCommitment;
+
+impl CommitmentScheme<Fr> for HashCommitment {
+    type Params = ();
+    type Commitment = Vec<u8>;
+
+    fn commit(polynomial: &ListOfProductsOfPolynomials<Fr>, _params: &()) -> Vec<u8> {
+        use blake2::{Blake2s, Digest};
+        let mut digest = Blake2s::new();
+        for mle in &polynomial.flattened_ml_extensions {
+            let mut buf = Vec::new();
+            mle.evaluations.serialize_uncompressed(&mut buf).unwrap();
+            digest.update(&buf);
+        }
+        digest.finalize().to_vec()
+    }
+}
+
+#[test]
+fn test_commit_and_prove() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let commitment = HashCommitment::commit(&poly, &());
+    let (proof, _) = MLSumcheck::prove_committed(&mut Blake2s512Rng::setup(), &commitment, &poly)
+        .expect("fail to prove");
+    let subclaim = MLSumcheck::verify_committed(
+        &mut Blake2s512Rng::setup(),
+        &commitment,
+        &poly.info(),
+        asserted_sum,
+        &proof,
+    )
+    .expect("fail to verify");
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+
+    let (other, _) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let other_commitment = HashCommitment::commit(&other, &());
+    assert!(MLSumcheck::verify_committed(
+        &mut Blake2s512Rng::setup(),
+        &other_commitment,
+        &poly.info(),
+        asserted_sum,
+        &proof,
+    )
+    .is_err());
+}
+
+#[test]
+fn test_check_openings() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (proof, prover_state) =
+        MLSumcheck::prove_as_subprotocol(&mut Blake2s512Rng::setup(), &poly)
+            .expect("fail to prove");
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+
+    let coeffs: Vec<Fr> = poly.products.iter().map(|(c, _)| *c).collect();
+    let mut openings: Vec<Vec<Fr>> = poly
+        .products
+        .iter()
+        .map(|(_, product)| {
+            product
+                .iter()
+                .map(|&i| {
+                    poly.flattened_ml_extensions[i]
+                        .evaluate(&subclaim.point)
+                        .unwrap()
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(openings, prover_state.final_multiplicand_evals());
+    assert!(subclaim.check_openings(&coeffs, &openings).unwrap());
+
+    openings[2][0] += Fr::one();
+    assert!(!subclaim.check_openings(&coeffs, &openings).unwrap());
+    assert!(subclaim.check_openings(&coeffs[..2], &openings).is_err());
+}
+
+#[test]
+fn test_subclaim_verify_value() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    let actual = poly.evaluate(&subclaim.point);
+    assert!(subclaim.verify_value(actual));
+    assert!(subclaim.verify_value_err(actual).is_ok());
+
+    let wrong = actual + Fr::one();
+    assert!(!subclaim.verify_value(wrong));
+    match subclaim.verify_value_err(wrong) {
+        Err(crate::Error::Reject(Some(reason))) => {
+            assert!(reason.contains("does not match the expected evaluation"))
+        }
+        _ => panic!("a wrong evaluation should be rejected"),
+    }
+}
+
+#[cfg(feature = "viz")]
+#[test]
+fn test_to_dot() {
+    let mut rng = test_rng();
+    let nv = 3;
+    let a = Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+    let b = Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+    let c = Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng));
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product(vec![a.clone(), b], Fr::from(3u64));
+    poly.add_product(vec![a, c], Fr::from(5u64));
+
+    let dot = poly.to_dot();
+    assert!(dot.starts_with("digraph polynomial {"));
+    // two products and three multiplicands
+    assert_eq!(dot.lines().filter(|l| l.contains("label=")).count(), 5);
+    assert_eq!(dot.matches(" -> ").count(), 4);
+    assert_eq!(dot.matches("arity: 2").count(), 2);
+}
+
+#[cfg(feature = "fold-trace")]
+#[test]
+fn test_prove_with_trace() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let (poly, _) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let (proof, trace) = MLSumcheck::prove_with_trace(&poly).expect("fail to prove");
+    let expected = MLSumcheck::prove(&poly).expect("fail to prove");
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+
+    assert_eq!(trace.len(), nv);
+    for (i, tables) in trace.iter().enumerate() {
+        assert_eq!(tables.len(), poly.flattened_ml_extensions.len());
+        assert!(tables
+            .iter()
+            .all(|t| t.evaluations.len() == 1 << (nv - i - 1)));
+    }
+    assert!(trace[nv - 1].iter().all(|t| t.evaluations.len() == 1));
+
+    // the last tables are the multiplicands at the point of the subclaim
+    let subclaim = MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof)
+        .expect("fail to verify");
+    for (table, original) in trace[nv - 1].iter().zip(&poly.flattened_ml_extensions) {
+        assert_eq!(
+            table.evaluations[0],
+            original.evaluate(&subclaim.point).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_wide_challenges() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fq, _>(5, (2, 4), 3, &mut rng);
+    let (proof, _) =
+        MLSumcheck::prove_with_wide_challenges::<Fq2>(&mut Blake2s512Rng::setup(), &poly)
+            .expect("fail to prove");
+    let subclaim = MLSumcheck::verify_with_wide_challenges(
+        &mut Blake2s512Rng::setup(),
+        &poly.info(),
+        asserted_sum,
+        &proof,
+    )
+    .expect("fail to verify");
+    // the challenges are not in the base field
+    assert!(subclaim.point.iter().all(|r| !r.c1.is_zero()));
+    assert_eq!(
+        poly.lift_to_extension::<Fq2>().evaluate(&subclaim.point),
+        subclaim.expected_evaluation
+    );
+    assert!(MLSumcheck::verify_with_wide_challenges(
+        &mut Blake2s512Rng::setup(),
+        &poly.info(),
+        asserted_sum + Fq::one(),
+        &proof,
+    )
+    .is_err());
+}
+
+/// The field with 17 elements and its quadratic extension, small enough to observe the soundness
+/// error of sumcheck.
+mod f17 {
+    use ark_ff::fields::{Fp2, Fp2Config, Fp64, MontBackend, MontConfig};
+    use ark_ff::MontFp;
+
+    #[derive(MontConfig)]
+    #[modulus = "17"]
+    #[generator = "3"]
+    pub(super) struct F17Config;
+    pub(super) type F17 = Fp64<MontBackend<F17Config, 1>>;
+
+    pub(super) struct F17SquaredConfig;
+
+    impl Fp2Config for F17SquaredConfig {
+        type Fp = F17;
+        // 3 is not a square modulo 17
+        const NONRESIDUE: F17 = MontFp!("3");
+        const FROBENIUS_COEFF_FP2_C1: &'static [F17] = &[MontFp!("1"), MontFp!("16")];
+    }
+
+    pub(super) type F17Squared = Fp2<F17SquaredConfig>;
+}
+
+#[test]
+fn test_wide_challenges_soundness() {
+    use f17::{F17Squared, F17};
+
+    const TRIALS: usize = 2000;
+    let mut rng = test_rng();
+    let mut accepted_single = 0;
+    let mut accepted_wide = 0;
+    for _ in 0..TRIALS {
+        let f = DenseMultilinearExtension::<F17>::rand(1, &mut rng);
+        let g = DenseMultilinearExtension::<F17>::rand(1, &mut rng);
+        let mut poly = ListOfProductsOfPolynomials::new(1);
+        poly.add_product(vec![Rc::new(f.clone()), Rc::new(g.clone())], F17::one());
+        let info = poly.info();
+
+        // the honest round polynomial is f(X) * g(X); the cheating prover adds
+        // e(X) = -delta * X * (X - 2), which adds delta to the sum and vanishes at 0 and 2 only, so
+        // the proof of the wrong sum is accepted iff the challenge is 0 or 2
+        let at = |h: &DenseMultilinearExtension<F17>, x: u64| {
+            h.evaluations[0] + (h.evaluations[1] - h.evaluations[0]) * F17::from(x)
+        };
+        let delta = F17::one();
+        let cheating: Vec<F17> = vec![
+            at(&f, 0) * at(&g, 0),
+            at(&f, 1) * at(&g, 1) + delta,
+            at(&f, 2) * at(&g, 2),
+        ];
+        let wrong_sum = cheating[0] + cheating[1];
+
+        let proof = vec![ProverMsg {
+            evaluations: cheating.clone(),
+        }];
+        let subclaim = MLSumcheck::verify(&info, wrong_sum, &proof).unwrap();
+        if poly.evaluate(&subclaim.point) == subclaim.expected_evaluation {
+            accepted_single += 1;
+        }
+
+        let wide_proof = vec![ProverMsg {
+            evaluations: cheating
+                .iter()
+                .map(|&x| F17Squared::from_base_prime_field(x))
+                .collect(),
+        }];
+        let subclaim = MLSumcheck::verify_with_wide_challenges(
+            &mut Blake2s512Rng::setup(),
+            &info,
+            wrong_sum,
+            &wide_proof,
+        )
+        .unwrap();
+        if poly
+            .lift_to_extension::<F17Squared>()
+            .evaluate(&subclaim.point)
+            == subclaim.expected_evaluation
+        {
+            accepted_wide += 1;
+        }
+    }
+    // the expected rates are 2 / 17, i.e. about 235 trials, and 2 / 289, i.e. about 14 trials
+    assert!(accepted_single > TRIALS / 17);
+    assert!(accepted_wide < TRIALS / 34);
+    assert!(accepted_wide < accepted_single);
+}
+
+#[test]
+fn test_prove_in_workspace() {
+    let mut rng = test_rng();
+    let mut workspace = ProverWorkspace::new();
+    for _ in 0..3 {
+        let (poly, asserted_sum) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+        let proof =
+            MLSumcheck::prove_in_workspace(&mut workspace, &poly, &mut Blake2s512Rng::setup())
+                .expect("fail to prove");
+        let expected = MLSumcheck::prove(&poly).expect("fail to prove");
+        assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+        MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+    }
+}
+
+#[test]
+fn test_fold_in_place() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(5, (2, 4), 2, &mut rng);
+    let challenges: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    let mut verifier_msg = None;
+    for challenge in &challenges {
+        IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        verifier_msg = Some(VerifierMsg {
+            randomness: *challenge,
+        });
+    }
+    IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+    for (folded, original) in prover_state
+        .flattened_ml_extensions
+        .iter()
+        .zip(poly.flattened_ml_extensions.iter())
+    {
+        assert_eq!(*folded, original.fix_variables(&challenges));
+    }
+}
+
+#[test]
+fn test_round_message_scaling() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (1, 4), 20, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    MLSumcheck::verify(&poly.info(), asserted_sum, &proof).expect("fail to verify");
+
+    // the first message, with the coefficients applied to every point
+    let expected: Vec<Fr> = (0..=poly.max_multiplicands as u64)
+        .map(|t| {
+            let t = Fr::from(t);
+            (0..1 << (nv - 1))
+                .map(|b| {
+                    poly.products
+                        .iter()
+                        .map(|(coefficient, product)| {
+                            product.iter().fold(*coefficient, |acc, &j| {
+                                let table = &poly.flattened_ml_extensions[j].evaluations;
+                                let (low, high) = (table[b << 1], table[(b << 1) + 1]);
+                                acc * (low + t * (high - low))
+                            })
+                        })
+                        .sum::<Fr>()
+                })
+                .sum()
+        })
+        .collect();
+    assert_eq!(proof[0].evaluations, expected);
+}
+
+/// oracle backed by a plain vector of evaluations
+struct VecOracle(Vec<Fr>);
+
+impl MultilinearOracle<Fr> for VecOracle {
+    fn num_vars(&self) -> usize {
+        self.0.len().trailing_zeros() as usize
+    }
+
+    fn evaluate_on_hypercube(&self, index: usize) -> Fr {
+        self.0[index]
+    }
+
+    fn fold(&mut self, r: Fr) {
+        self.0 = self
+            .0
+            .chunks(2)
+            .map(|pair| pair[0] + (pair[1] - pair[0]) * r)
+            .collect();
+    }
+}
+
+#[test]
+fn test_prove_from_oracles() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let oracles = poly
+        .flattened_ml_extensions
+        .iter()
+        .map(|mle| VecOracle(mle.evaluations.clone()))
+        .collect();
+    let mut oracle_state =
+        IPForMLSumcheck::prover_init_from_oracles(poly.products.clone(), oracles);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    assert_eq!(
+        oracle_state.max_multiplicands,
+        prover_state.max_multiplicands
+    );
+    let mut verifier_msg = None;
+    for _ in 0..poly.num_variables {
+        let expected = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        let msg = IPForMLSumcheck::prove_round(&mut oracle_state, &verifier_msg);
+        assert_eq!(msg.evaluations, expected.evaluations);
+        verifier_msg = Some(VerifierMsg {
+            randomness: Fr::rand(&mut rng),
+        });
+    }
+}
+
+#[test]
+fn test_verify_candidates() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).expect("fail to prove");
+    let mut candidates: Vec<Fr> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+    assert!(
+        MLSumcheck::verify_candidates(&poly.info(), &candidates, &proof)
+            .expect("fail to verify")
+            .is_none()
+    );
+
+    candidates[3] = asserted_sum;
+    let (index, subclaim) = MLSumcheck::verify_candidates(&poly.info(), &candidates, &proof)
+        .expect("fail to verify")
+        .expect("no candidate matches");
+    assert_eq!(index, 3);
+    assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+}
+
+#[test]
+fn test_recursion_constraint_estimate() {
+    let estimate = |max_multiplicands, num_variables| {
+        PolynomialInfo {
+            max_multiplicands,
+            num_variables,
+        }
+        .recursion_constraint_estimate()
+        .unwrap()
+    };
+    // 4 * 1 + 1 * 300 per round
+    assert_eq!(estimate(1, 8), 8 * 304);
+    // 4 * 3 + 2 * 300 per round
+    assert_eq!(estimate(3, 10), 6120);
+    // 4 * 5 + 3 * 300 per round
+    assert_eq!(estimate(5, 20), 18400);
+    assert_eq!(estimate(5, 0), 0);
+}
+
+#[test]
+fn test_prove_broadcast() {
+    let mut rng = test_rng();
+    let f = DenseMultilinearExtension::<Fr>::rand(4, &mut rng);
+    let g = DenseMultilinearExtension::<Fr>::rand(2, &mut rng);
+    let h = DenseMultilinearExtension::<Fr>::rand(4, &mut rng);
+    let coefficient = Fr::rand(&mut rng);
+    let products = vec![(coefficient, vec![0, 1]), (Fr::one(), vec![1, 2, 1])];
+    let proof =
+        MLSumcheck::prove_broadcast(4, &products, vec![f.clone(), g.clone(), h.clone()]).unwrap();
+
+    // g does not depend on the last two variables
+    let broadcast = DenseMultilinearExtension::from_evaluations_vec(
+        4,
+        (0..16).map(|i| g.evaluations[i & 3]).collect(),
+    );
+    let (f, broadcast, h) = (Rc::new(f), Rc::new(broadcast), Rc::new(h));
+    let mut poly = ListOfProductsOfPolynomials::new(4);
+    poly.add_product(vec![f, broadcast.clone()], coefficient);
+    poly.add_product(vec![broadcast.clone(), h, broadcast], Fr::one());
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+    let subclaim = MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof)
+        .expect("broadcast proof should verify");
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let too_many_variables = DenseMultilinearExtension::<Fr>::rand(5, &mut rng);
+    assert!(MLSumcheck::prove_broadcast(
+        4,
+        &products,
+        vec![too_many_variables, g.clone(), g.clone()]
+    )
+    .is_err());
+    assert!(MLSumcheck::prove_broadcast(4, &[(Fr::one(), vec![3])], vec![g]).is_err());
+}
+
+#[test]
+fn test_verification_accumulator() {
+    let mut rng = test_rng();
+    let polynomials: Vec<_> = (0..3)
+        .map(|i| random_instance::<Fr, _>(4 + i, (2, 4), 3, &mut rng))
+        .collect();
+    let mut accumulator = VerificationAccumulator::new();
+    for (poly, sum) in &polynomials {
+        let proof = MLSumcheck::prove(poly).unwrap();
+        accumulator.add(&poly.info(), *sum, &proof).unwrap();
+    }
+    assert_eq!(accumulator.num_proofs(), 3);
+    let (poly, sum) = &polynomials[0];
+    let proof = MLSumcheck::prove(poly).unwrap();
+    assert!(accumulator
+        .add(&poly.info(), *sum + Fr::one(), &proof)
+        .is_err());
+    assert_eq!(accumulator.num_proofs(), 3);
+
+    let open = |points: &[Vec<Fr>], coefficients: &[Fr]| {
+        assert_eq!(points.len(), 3);
+        Ok::<Fr, crate::Error>(
+            polynomials
+                .iter()
+                .zip(points.iter().zip(coefficients))
+                .map(|((poly, _), (point, c))| poly.evaluate(point) * c)
+                .sum(),
+        )
+    };
+    let mut second = VerificationAccumulator::new();
+    for (poly, sum) in &polynomials {
+        second
+            .add(&poly.info(), *sum, &MLSumcheck::prove(poly).unwrap())
+            .unwrap();
+    }
+    assert!(accumulator.finalize(open).unwrap());
+    let wrong_opening =
+        |points: &[Vec<Fr>], coefficients: &[Fr]| open(points, coefficients).map(|e| e + Fr::one());
+    assert!(!second.finalize(wrong_opening).unwrap());
+}
+
+#[test]
+fn test_prove_with_permutation() {
+    let mut rng = test_rng();
+    let (poly, sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let identity: Vec<usize> = (0..5).collect();
+    let (proof, _) =
+        MLSumcheck::prove_with_permutation(&poly, &identity, &mut Blake2s512Rng::setup()).unwrap();
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+
+    let perm = [3, 0, 4, 2, 1];
+    let (proof, prover_state) =
+        MLSumcheck::prove_with_permutation(&poly, &perm, &mut Blake2s512Rng::setup()).unwrap();
+    let subclaim = MLSumcheck::verify_with_permutation(
+        &poly.info(),
+        sum,
+        &proof,
+        &perm,
+        &mut Blake2s512Rng::setup(),
+    )
+    .expect("permuted proof should verify");
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    for (j, &i) in perm.iter().enumerate() {
+        assert_eq!(subclaim.point[i], prover_state.randomness[j]);
+    }
+
+    for bad in [
+        &[0, 1, 2, 3][..],
+        &[0, 1, 2, 3, 3],
+        &[0, 1, 2, 3, 5],
+        &[0, 1, 2, 3, 4, 5],
+    ] {
+        assert!(
+            MLSumcheck::prove_with_permutation(&poly, bad, &mut Blake2s512Rng::setup()).is_err()
+        );
+        assert!(MLSumcheck::verify_with_permutation(
+            &poly.info(),
+            sum,
+            &proof,
+            bad,
+            &mut Blake2s512Rng::setup()
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn test_subclaim_finalize_with() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(4, (1, 3), 4, &mut rng);
+    let (proof, prover_state) =
+        MLSumcheck::prove_as_subprotocol(&mut Blake2s512Rng::setup(), &poly).unwrap();
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    let coeffs: Vec<Fr> = poly.products.iter().map(|(c, _)| *c).collect();
+    let mut evaluations = prover_state.final_multiplicand_evals();
+    assert!(subclaim.finalize_with(&evaluations, &coeffs).unwrap());
+
+    evaluations[0][0] += Fr::one();
+    assert!(!subclaim.finalize_with(&evaluations, &coeffs).unwrap());
+    assert!(subclaim.finalize_with(&evaluations[1..], &coeffs).is_err());
+
+    // `finalize_with` is `check_openings` with its arguments swapped
+    let honest = prover_state.final_multiplicand_evals();
+    for openings in [&honest, &evaluations] {
+        assert_eq!(
+            subclaim.finalize_with(openings, &coeffs).unwrap(),
+            subclaim.check_openings(&coeffs, openings).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_prove_to_writer() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let mut buffer = Vec::new();
+    let prover_state =
+        MLSumcheck::prove_to_writer(&poly, &mut buffer, &mut Blake2s512Rng::setup()).unwrap();
+    let subclaim = MLSumcheck::verify_from_reader(
+        &poly.info(),
+        asserted_sum,
+        &buffer[..],
+        &mut Blake2s512Rng::setup(),
+    )
+    .expect("streamed proof should verify");
+    assert_eq!(prover_state.challenges(), &subclaim.point[..]);
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let mut expected = Vec::new();
+    for msg in MLSumcheck::prove(&poly).unwrap() {
+        msg.serialize_compressed(&mut expected).unwrap();
+    }
+    assert_eq!(buffer, expected);
+
+    let truncated = &buffer[..buffer.len() - 1];
+    assert!(MLSumcheck::verify_from_reader(
+        &poly.info(),
+        asserted_sum,
+        truncated,
+        &mut Blake2s512Rng::setup()
+    )
+    .is_err());
+    assert!(MLSumcheck::verify_from_reader(
+        &poly.info(),
+        asserted_sum + Fr::one(),
+        &buffer[..],
+        &mut Blake2s512Rng::setup()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_validate_canonical() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(4, (2, 3), 2, &mut rng);
+    let mut proof = MLSumcheck::prove(&poly).unwrap();
+    MLSumcheck::validate_canonical(&proof).unwrap();
+
+    // the modulus encodes zero in non-reduced form; it starts after the two lengths
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert!(Proof::<Fr>::deserialize_compressed(&bytes[..]).is_ok());
+    bytes[16..48].copy_from_slice(&Fr::MODULUS.to_bytes_le());
+    assert!(Proof::<Fr>::deserialize_compressed(&bytes[..]).is_err());
+    assert!(Proof::<Fr>::deserialize_uncompressed(&bytes[..]).is_err());
+
+    proof[1].evaluations[0] = Fr::new_unchecked(Fr::MODULUS);
+    assert!(MLSumcheck::validate_canonical(&proof).is_err());
+}
+
+#[test]
+fn test_subclaim_concat() {
+    let mut rng = test_rng();
+    let f = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+    let g = DenseMultilinearExtension::<Fr>::rand(2, &mut rng);
+    let subclaim_of = |mle: &DenseMultilinearExtension<Fr>| {
+        let mut poly = ListOfProductsOfPolynomials::new(mle.num_vars);
+        poly.add_product(vec![Rc::new(mle.clone())], Fr::one());
+        let proof = MLSumcheck::prove(&poly).unwrap();
+        MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof).unwrap()
+    };
+    let (subclaim_f, subclaim_g) = (subclaim_of(&f), subclaim_of(&g));
+    let combined = subclaim_f.concat(&subclaim_g);
+    assert_eq!(combined.point.len(), 5);
+    assert_eq!(&combined.point[..3], &subclaim_f.point[..]);
+    assert_eq!(&combined.point[3..], &subclaim_g.point[..]);
+
+    let tensor = TensorProduct::new(f, g).materialize();
+    assert_eq!(
+        tensor.evaluate(&combined.point).unwrap(),
+        combined.expected_evaluation
+    );
+}
+
+#[test]
+fn test_proof_encoding() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 5), 3, &mut rng);
+    let evaluations = MLSumcheck::prove_with_encoding(&poly, ProofEncoding::Evaluations).unwrap();
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&evaluations, &expected), None);
+    let coefficients = MLSumcheck::prove_with_encoding(&poly, ProofEncoding::Coefficients).unwrap();
+    let round_trip = MLSumcheck::convert_encoding(
+        &MLSumcheck::convert_encoding(
+            &coefficients,
+            ProofEncoding::Coefficients,
+            ProofEncoding::Evaluations,
+        ),
+        ProofEncoding::Evaluations,
+        ProofEncoding::Coefficients,
+    );
+    assert_eq!(
+        MLSumcheck::first_divergence(&round_trip, &coefficients),
+        None
+    );
+    // the first round polynomial does not depend on any challenge
+    assert_eq!(
+        MLSumcheck::convert_encoding(
+            &coefficients,
+            ProofEncoding::Coefficients,
+            ProofEncoding::Evaluations
+        )[0]
+        .evaluations,
+        evaluations[0].evaluations
+    );
+
+    let info = poly.info();
+    let subclaim = MLSumcheck::verify_with_encoding(
+        &info,
+        asserted_sum,
+        &coefficients,
+        ProofEncoding::Coefficients,
+    )
+    .expect("coefficient proof should verify");
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+    assert!(MLSumcheck::verify_with_encoding(
+        &info,
+        asserted_sum + Fr::one(),
+        &coefficients,
+        ProofEncoding::Coefficients
+    )
+    .is_err());
+    // the transcript absorbs the round polynomials as sent, so a converted proof does not verify
+    let converted = MLSumcheck::convert_encoding(
+        &evaluations,
+        ProofEncoding::Evaluations,
+        ProofEncoding::Coefficients,
+    );
+    assert!(MLSumcheck::verify_with_encoding(
+        &info,
+        asserted_sum,
+        &converted,
+        ProofEncoding::Coefficients
+    )
+    .is_err());
+    // a coefficient proof is not an evaluation proof
+    assert!(MLSumcheck::verify_with_encoding(
+        &info,
+        asserted_sum,
+        &coefficients,
+        ProofEncoding::Evaluations
+    )
+    .is_err());
+}
+
+#[test]
+fn test_prove_from_oracle() {
+    let mut rng = test_rng();
+    let mle = DenseMultilinearExtension::<Fr>::rand(5, &mut rng);
+    let oracle = |point: &[bool]| {
+        let index = point
+            .iter()
+            .enumerate()
+            .fold(0, |index, (i, &bit)| index | (usize::from(bit) << i));
+        mle.evaluations[index]
+    };
+    let (proof, _) =
+        MLSumcheck::prove_from_oracle(5, 1, oracle, &mut Blake2s512Rng::setup()).unwrap();
+    let mut poly = ListOfProductsOfPolynomials::new(5);
+    poly.add_product(vec![Rc::new(mle.clone())], Fr::one());
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+
+    // padded to 3 multiplicands
+    let (proof, _) =
+        MLSumcheck::prove_from_oracle(5, 3, oracle, &mut Blake2s512Rng::setup()).unwrap();
+    let info = PolynomialInfo {
+        max_multiplicands: 3,
+        num_variables: 5,
+    };
+    let sum = mle.evaluations.iter().sum();
+    let subclaim = MLSumcheck::verify(&info, sum, &proof).expect("oracle proof should verify");
+    assert_eq!(
+        mle.evaluate(&subclaim.point).unwrap(),
+        subclaim.expected_evaluation
+    );
+    assert!(MLSumcheck::prove_from_oracle(5, 0, oracle, &mut Blake2s512Rng::setup()).is_err());
+    let unreachable = |_: &[bool]| -> Fr { panic!("the oracle should not be queried") };
+    assert!(matches!(
+        MLSumcheck::prove_from_oracle(0, 1, unreachable, &mut Blake2s512Rng::setup()),
+        Err(crate::Error::ConstantPolynomial)
+    ));
+    let too_many_variables = usize::BITS as usize;
+    assert!(MLSumcheck::prove_from_oracle(
+        too_many_variables,
+        1,
+        unreachable,
+        &mut Blake2s512Rng::setup()
+    )
+    .is_err());
+
+    // raising the degree never lowers it
+    poly.raise_degree(3);
+    poly.raise_degree(2);
+    assert_eq!(poly.max_multiplicands, 3);
+    assert_eq!(
+        MLSumcheck::first_divergence(&proof, &MLSumcheck::prove(&poly).unwrap()),
+        None
+    );
+}
+
+#[test]
+fn test_prove_with_digest() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (proof, prover_digest) = MLSumcheck::prove_with_digest(&poly).unwrap();
+    let (subclaim, verifier_digest) =
+        MLSumcheck::verify_with_digest(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(prover_digest, verifier_digest);
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let (other, _) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (_, other_digest) = MLSumcheck::prove_with_digest(&other).unwrap();
+    assert_ne!(prover_digest, other_digest);
+}
+
+#[test]
+fn test_small_coefficients() {
+    let mut rng = test_rng();
+    let f = Rc::new(DenseMultilinearExtension::<Fr>::rand(4, &mut rng));
+    let g = Rc::new(DenseMultilinearExtension::<Fr>::rand(4, &mut rng));
+    let mut small = ListOfProductsOfPolynomials::<Fr, u64>::new_with_coefficients(4);
+    small.add_product(vec![f.clone(), g.clone()], 3);
+    small.add_product(vec![g.clone()], u64::MAX);
+    let mut poly = ListOfProductsOfPolynomials::new(4);
+    poly.add_product(vec![f, g.clone()], Fr::from(3u64));
+    poly.add_product(vec![g], Fr::from(u64::MAX));
+
+    let proof = MLSumcheck::prove(&small).unwrap();
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+    let subclaim =
+        MLSumcheck::verify(&small.info(), MLSumcheck::extract_sum(&proof), &proof).unwrap();
+    assert_eq!(
+        small.evaluate(&subclaim.point),
+        subclaim.expected_evaluation
+    );
+}
+
+#[test]
+fn test_try_add_product_rejects_malformed_tables() {
+    let mut rng = test_rng();
+    let good = Rc::new(DenseMultilinearExtension::<Fr>::rand(2, &mut rng));
+    let short = Rc::new(DenseMultilinearExtension {
+        evaluations: vec![Fr::one(); 3],
+        num_vars: 2,
+    });
+    let mut poly = ListOfProductsOfPolynomials::new(2);
+    let err = poly
+        .try_add_product(vec![good.clone(), short], Fr::one())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "product has a multiplicand with 3 evaluations for 2 variables"
+    );
+    assert_eq!(poly.num_products(), 0);
+    assert_eq!(poly.max_multiplicands, 0);
+    assert!(poly.flattened_ml_extensions.is_empty());
+
+    let wide = Rc::new(DenseMultilinearExtension::<Fr>::rand(3, &mut rng));
+    assert!(poly.try_add_product(vec![wide], Fr::one()).is_err());
+    poly.try_add_product(vec![good], Fr::one()).unwrap();
+    assert_eq!(poly.num_products(), 1);
+}
+
+#[test]
+#[should_panic(expected = "product has a multiplicand with 5 evaluations for 2 variables")]
+fn test_add_product_panics_on_malformed_table() {
+    let mut poly = ListOfProductsOfPolynomials::new(2);
+    poly.add_product(
+        vec![Rc::new(DenseMultilinearExtension {
+            evaluations: vec![Fr::one(); 5],
+            num_vars: 2,
+        })],
+        Fr::one(),
+    );
+}
+
+#[test]
+fn test_prove_reflected() {
+    let mut rng = test_rng();
+    let f = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+    let g = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+    let coefficient = Fr::rand(&mut rng);
+    let products = vec![(coefficient, vec![0, 1]), (Fr::one(), vec![1])];
+    let oracles: Vec<Box<dyn MultilinearOracle<Fr>>> =
+        vec![Box::new(f.clone()), Box::new(Reflected::new(g.clone()))];
+    let proof = MLSumcheck::prove_with_oracles(&products, oracles).unwrap();
+
+    let reflected = Rc::new(DenseMultilinearExtension::from_evaluations_vec(
+        3,
+        (0..8).map(|i| g.evaluations[!i & 7]).collect(),
+    ));
+    let mut poly = ListOfProductsOfPolynomials::new(3);
+    poly.add_product(vec![Rc::new(f), reflected.clone()], coefficient);
+    poly.add_product(vec![reflected], Fr::one());
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+    let subclaim = MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof)
+        .expect("reflected proof should verify");
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let oracles: Vec<Box<dyn MultilinearOracle<Fr>>> = vec![
+        Box::new(DenseMultilinearExtension::<Fr>::rand(4, &mut rng)),
+        Box::new(Reflected::new(g)),
+    ];
+    assert!(MLSumcheck::prove_with_oracles(&products, oracles).is_err());
+}
+
+#[cfg(all(feature = "tracing", feature = "std"))]
+#[test]
+fn test_tracing_spans() {
+    use ark_std::fmt::Debug;
+    use ark_std::string::String;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// records the name and fields of every new span
+    struct SpanRecorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        next_id: AtomicU64,
+    }
+
+    struct FieldWriter(String);
+
+    impl Visit for FieldWriter {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut writer = FieldWriter(span.metadata().name().to_string());
+            span.record(&mut writer);
+            self.spans.lock().unwrap().push(writer.0);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed))
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(3, (2, 3), 2, &mut rng);
+    let spans = Arc::new(Mutex::new(Vec::new()));
+    let recorder = SpanRecorder {
+        spans: spans.clone(),
+        next_id: AtomicU64::new(1),
+    };
+    tracing::subscriber::with_default(recorder, || {
+        let proof = MLSumcheck::prove(&poly).unwrap();
+        MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    });
+
+    let info = format!(
+        "num_variables=3 max_multiplicands={}",
+        poly.max_multiplicands
+    );
+    let expected: Vec<String> = ark_std::iter::once(format!("prove {}", info))
+        .chain((0..3).map(|i| format!("prove_round round={}", i)))
+        .chain(ark_std::iter::once(format!("verify {}", info)))
+        .chain((0..3).map(|i| format!("verify_round round={}", i)))
+        .collect();
+    assert_eq!(*spans.lock().unwrap(), expected);
+}
+
+#[test]
+fn test_verify_with_sum_oracle() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(4, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    // the oracle samples an opening challenge from the transcript before returning the sum
+    let mut prover_challenge = Fr::zero();
+    let (proof, _) =
+        MLSumcheck::prove_with_sum_oracle(&mut Blake2s512Rng::setup(), &poly, |fs_rng| {
+            prover_challenge = Fr::rand(fs_rng);
+            asserted_sum
+        })
+        .unwrap();
+    let mut verifier_challenge = Fr::zero();
+    let subclaim = MLSumcheck::verify_with_sum_oracle(
+        &mut Blake2s512Rng::setup(),
+        &info,
+        |fs_rng| {
+            verifier_challenge = Fr::rand(fs_rng);
+            asserted_sum
+        },
+        &proof,
+    )
+    .expect("proof should verify with the same sum oracle");
+    assert_eq!(prover_challenge, verifier_challenge);
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    // the challenges depend on the oracle's use of the transcript
+    assert!(MLSumcheck::verify(&info, asserted_sum, &proof).is_err());
+    assert!(MLSumcheck::verify_with_sum_oracle(
+        &mut Blake2s512Rng::setup(),
+        &info,
+        |_| asserted_sum,
+        &proof
+    )
+    .is_err());
+    assert!(MLSumcheck::prove_with_sum_oracle(
+        &mut Blake2s512Rng::setup(),
+        &poly,
+        |_| asserted_sum + Fr::one()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_verify_split_proof() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let expected = MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+
+    for k in 0..=5 {
+        let (prefix, suffix) = MLSumcheck::split_proof(&proof, k).unwrap();
+        let mut fs_rng = Blake2s512Rng::setup();
+        let handoff = MLSumcheck::verify_prefix(&mut fs_rng, &info, asserted_sum, &prefix)
+            .expect("prefix should verify");
+        assert_eq!(handoff.point, expected.point[..k]);
+        let subclaim = MLSumcheck::verify_suffix(&mut fs_rng, handoff, &info, &suffix, k)
+            .expect("suffix should verify");
+        assert_eq!(subclaim.point, expected.point);
+        assert_eq!(subclaim.expected_evaluation, expected.expected_evaluation);
+    }
+
+    let (prefix, suffix) = MLSumcheck::split_proof(&proof, 2).unwrap();
+    let mut fs_rng = Blake2s512Rng::setup();
+    let handoff = MLSumcheck::verify_prefix(&mut fs_rng, &info, asserted_sum, &prefix).unwrap();
+    assert!(
+        MLSumcheck::verify_suffix(&mut fs_rng.clone(), handoff.clone(), &info, &suffix, 3).is_err()
+    );
+    assert!(MLSumcheck::verify_suffix(&mut fs_rng, handoff, &info, &suffix[1..], 2).is_err());
+    assert!(MLSumcheck::verify_prefix(
+        &mut Blake2s512Rng::setup(),
+        &info,
+        asserted_sum + Fr::one(),
+        &prefix
+    )
+    .is_err());
+    assert!(matches!(
+        MLSumcheck::split_proof(&proof, 6),
+        Err(crate::Error::WrongProofLength {
+            expected: 6,
+            got: 5
+        })
+    ));
+}
+
+#[test]
+fn test_prove_power_sum() {
+    let mut rng = test_rng();
+    let f = DenseMultilinearExtension::<Fr>::rand(4, &mut rng);
+    for power in [1usize, 2, 4, 8] {
+        let claimed_sum: Fr = f.evaluations.iter().map(|e| e.pow([power as u64])).sum();
+        let proof = MLSumcheck::prove_power_sum(&f, power, &mut Blake2s512Rng::setup()).unwrap();
+        assert_eq!(proof.proofs.len(), power.trailing_zeros().max(1) as usize);
+        let subclaim = MLSumcheck::verify_power_sum(
+            4,
+            power,
+            claimed_sum,
+            &proof,
+            &mut Blake2s512Rng::setup(),
+        )
+        .expect("power sum should verify");
+        assert_eq!(
+            f.evaluate(&subclaim.point).unwrap(),
+            subclaim.expected_evaluation
+        );
+        assert!(MLSumcheck::verify_power_sum(
+            4,
+            power,
+            claimed_sum + Fr::one(),
+            &proof,
+            &mut Blake2s512Rng::setup()
+        )
+        .is_err());
+    }
+
+    let proof = MLSumcheck::prove_power_sum(&f, 4, &mut Blake2s512Rng::setup()).unwrap();
+    let claimed_sum: Fr = f.evaluations.iter().map(|e| e.square().square()).sum();
+    let mut tampered = proof.clone();
+    tampered.evaluations[0] += Fr::one();
+    assert!(MLSumcheck::verify_power_sum(
+        4,
+        4,
+        claimed_sum,
+        &tampered,
+        &mut Blake2s512Rng::setup()
+    )
+    .is_err());
+    assert!(
+        MLSumcheck::verify_power_sum(4, 8, claimed_sum, &proof, &mut Blake2s512Rng::setup())
+            .is_err()
+    );
+    assert!(MLSumcheck::prove_power_sum(&f, 3, &mut Blake2s512Rng::setup()).is_err());
+    assert!(MLSumcheck::prove_power_sum(&f, 0, &mut Blake2s512Rng::setup()).is_err());
+}
+
+#[test]
+fn test_prover_state_progress() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(4, (2, 3), 2, &mut rng);
+    let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+    assert_eq!(prover_state.rounds_completed(), 0);
+    assert_eq!(prover_state.remaining_variables(), 4);
+
+    IPForMLSumcheck::prove_round(&mut prover_state, &None);
+    assert_eq!(prover_state.rounds_completed(), 0);
+    assert_eq!(prover_state.remaining_variables(), 4);
+    for round in 1..4 {
+        let verifier_msg = Some(VerifierMsg {
+            randomness: Fr::rand(&mut rng),
+        });
+        IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+        assert_eq!(prover_state.rounds_completed(), round);
+        assert_eq!(prover_state.remaining_variables(), 4 - round);
+    }
+
+    let (_, prover_state) =
+        MLSumcheck::prove_as_subprotocol(&mut Blake2s512Rng::setup(), &poly).unwrap();
+    assert_eq!(prover_state.rounds_completed(), 4);
+    assert_eq!(prover_state.remaining_variables(), 0);
+}
+
+#[test]
+fn test_sumcheck_prover_finished() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(3, (2, 3), 2, &mut rng);
+    let mut prover = SumcheckProver::new(&poly).unwrap();
+    let verifier_msg = Some(VerifierMsg {
+        randomness: Fr::one(),
+    });
+    assert!(prover.next_round(&verifier_msg).is_err());
+
+    let mut fs_rng = Blake2s512Rng::setup();
+    let mut proof = Vec::new();
+    let mut verifier_msg = None;
+    for round in 0..3 {
+        assert!(!prover.is_finished());
+        let msg = prover.next_round(&verifier_msg).unwrap();
+        if round == 0 {
+            fs_rng.feed_batch(&[&poly.info(), &msg]).unwrap();
+        } else {
+            fs_rng.feed(&msg).unwrap();
+        }
+        proof.push(msg);
+        verifier_msg = Some(IPForMLSumcheck::sample_round(&mut fs_rng));
+    }
+    assert!(prover.is_finished());
+    assert!(matches!(
+        prover.next_round(&verifier_msg),
+        Err(crate::Error::ProtocolFinished)
+    ));
+    assert_eq!(
+        MLSumcheck::first_divergence(&proof, &MLSumcheck::prove(&poly).unwrap()),
+        None
+    );
+    MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(prover.into_state().rounds_completed(), 2);
+
+    assert!(SumcheckProver::new(&ListOfProductsOfPolynomials::<Fr>::new(3)).is_err());
+}
+
+#[test]
+fn test_precompute_eq_table() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let r: Vec<Fr> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let table = precompute_eq_table(&r);
+    assert_eq!(table.len(), 1 << nv);
+    for _ in 0..20 {
+        let index = rng.gen_range(0..1 << nv);
+        let point: Vec<Fr> = (0..nv)
+            .map(|j| {
+                if (index >> j) & 1 == 1 {
+                    Fr::one()
+                } else {
+                    Fr::zero()
+                }
+            })
+            .collect();
+        assert_eq!(table[index], eq_eval(&r, &point));
+    }
+    assert_eq!(precompute_eq_table::<Fr>(&[]), vec![Fr::one()]);
+}
+
+#[test]
+fn test_batch_challenge_source() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let challenges: Vec<Fr> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut source = BatchChallengeSource::new(challenges.clone());
+    let (proof, prover_state) = MLSumcheck::prove_with_challenges(&poly, &mut source).unwrap();
+    assert_eq!(source.remaining(), 0);
+    assert_eq!(prover_state.randomness, challenges);
+
+    let mut source = BatchChallengeSource::new(challenges.clone());
+    let subclaim =
+        MLSumcheck::verify_with_challenges(&poly.info(), asserted_sum, &proof, &mut source)
+            .unwrap();
+    assert_eq!(subclaim.point, challenges);
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let mut source = BatchChallengeSource::new(challenges);
+    assert!(MLSumcheck::verify_with_challenges(
+        &poly.info(),
+        asserted_sum + Fr::one(),
+        &proof,
+        &mut source
+    )
+    .is_err());
+}
+
+#[test]
+fn test_wrong_proof_length() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(4, (2, 3), 2, &mut rng);
+    let mut proof = MLSumcheck::prove(&poly).unwrap();
+    MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+
+    let truncated = proof[..3].to_vec();
+    assert!(matches!(
+        MLSumcheck::verify(&poly.info(), asserted_sum, &truncated),
+        Err(crate::Error::WrongProofLength {
+            expected: 4,
+            got: 3
+        })
+    ));
+    proof.push(proof[0].clone());
+    assert!(matches!(
+        MLSumcheck::verify(&poly.info(), asserted_sum, &proof),
+        Err(crate::Error::WrongProofLength {
+            expected: 4,
+            got: 5
+        })
+    ));
+}
+
+#[test]
+fn test_verify_collect_witness() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let (subclaim, witness) =
+        MLSumcheck::verify_collect_witness(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(witness.len(), proof.len());
+    let challenges: Vec<Fr> = witness.iter().map(|(_, r)| *r).collect();
+    assert_eq!(challenges, subclaim.point);
+    let msgs: Proof<Fr> = witness.into_iter().map(|(msg, _)| msg).collect();
+    assert_eq!(MLSumcheck::first_divergence(&msgs, &proof), None);
+
+    assert!(
+        MLSumcheck::verify_collect_witness(&poly.info(), asserted_sum + Fr::one(), &proof).is_err()
+    );
+}
+
+#[test]
+fn test_power_product_degree() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_power(Fr::one(), &f, 3);
+    assert_eq!(poly.flattened_ml_extensions.len(), 1);
+    let info = poly.info();
+    assert_eq!(info.max_multiplicands, 3);
+    assert_eq!(info.expected_round_message_len().unwrap(), 4);
+
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    assert!(proof
+        .iter()
+        .all(|msg| msg.evaluations.len() == info.expected_round_message_len().unwrap()));
+    let asserted_sum: Fr = f.evaluations.iter().map(|e| *e * e * e).sum();
+    MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+}
+
+#[test]
+fn test_add_scaled() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let (f, sum_f) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let (g, sum_g) = random_instance::<Fr, _>(nv, (1, 3), 2, &mut rng);
+    let two = Fr::from(2u64);
+    let three = Fr::from(3u64);
+
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_scaled(two, &f).unwrap();
+    poly.add_scaled(-three, &g).unwrap();
+    assert_eq!(poly.num_products(), f.num_products() + g.num_products());
+    let asserted_sum = two * sum_f - three * sum_g;
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::extract_sum(&proof), asserted_sum);
+    MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+
+    let (h, _) = random_instance::<Fr, _>(nv + 1, (1, 3), 2, &mut rng);
+    assert!(poly.add_scaled(two, &h).is_err());
+    assert_eq!(poly.num_products(), f.num_products() + g.num_products());
+}
+
+#[test]
+fn test_round_check_failed() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let mut corrupted = proof.clone();
+    corrupted[2].evaluations[0] += Fr::one();
+
+    match MLSumcheck::verify(&poly.info(), asserted_sum, &corrupted) {
+        Err(crate::Error::RoundCheckFailed(failure)) => {
+            assert_eq!(failure.round, 2);
+            assert_eq!(failure.g0, format!("{}", corrupted[2].evaluations[0]));
+            assert_eq!(failure.g1, format!("{}", corrupted[2].evaluations[1]));
+        }
+        _ => panic!("a corrupted round should fail its check"),
+    }
+    match MLSumcheck::verify(&poly.info(), asserted_sum + Fr::one(), &proof) {
+        Err(crate::Error::RoundCheckFailed(failure)) => {
+            assert_eq!(failure.round, 0);
+            assert_eq!(failure.expected, format!("{}", asserted_sum + Fr::one()));
+        }
+        _ => panic!("a wrong sum should fail the check of the first round"),
+    }
+
+    // the rounds of both polynomials are checked together, then the ones of the first alone
+    let (poly_0, sum_0) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let (poly_1, sum_1) = random_instance::<Fr, _>(3, (2, 4), 3, &mut rng);
+    let ((proof_0, proof_1), _) = MLSumcheck::multi_degree_prove_as_subprotocol(
+        &mut Blake2s512Rng::setup(),
+        &poly_0,
+        &poly_1,
+    )
+    .unwrap();
+    let verify_multi_degree = |proof_0: &Proof<Fr>, proof_1: &Proof<Fr>| {
+        MLSumcheck::multi_degree_verify_as_subprotocol(
+            &mut Blake2s512Rng::setup(),
+            (&poly_0.info(), &poly_1.info()),
+            sum_0 + sum_1,
+            (proof_0, proof_1),
+        )
+    };
+    let mut corrupted_1 = proof_1.clone();
+    corrupted_1[1].evaluations[0] += Fr::one();
+    match verify_multi_degree(&proof_0, &corrupted_1) {
+        Err(crate::Error::RoundCheckFailed(failure)) => {
+            assert_eq!(failure.round, 1);
+            let g0 = proof_0[1].evaluations[0] + corrupted_1[1].evaluations[0];
+            let g1 = proof_0[1].evaluations[1] + corrupted_1[1].evaluations[1];
+            assert_eq!(failure.g0, format!("{}", g0));
+            assert_eq!(failure.g1, format!("{}", g1));
+        }
+        _ => panic!("a corrupted combined round should fail its check"),
+    }
+    let mut corrupted_0 = proof_0.clone();
+    corrupted_0[4].evaluations[1] += Fr::one();
+    match verify_multi_degree(&corrupted_0, &proof_1) {
+        Err(crate::Error::RoundCheckFailed(failure)) => {
+            assert_eq!(failure.round, 4);
+            assert_eq!(failure.g0, format!("{}", corrupted_0[4].evaluations[0]));
+            assert_eq!(failure.g1, format!("{}", corrupted_0[4].evaluations[1]));
+        }
+        _ => panic!("a corrupted round of the first polynomial should fail its check"),
+    }
+}
+
+#[test]
+fn test_verify_continuing() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let mut prover_rng = Blake2s512Rng::setup();
+    let (proof, _) = MLSumcheck::prove_as_subprotocol(&mut prover_rng, &poly).unwrap();
+    let (subclaim, mut verifier_rng) =
+        MLSumcheck::verify_continuing(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    let next_value = Fr::rand(&mut rng);
+    prover_rng.feed(&next_value).unwrap();
+    verifier_rng.feed(&next_value).unwrap();
+    assert_eq!(Fr::rand(&mut prover_rng), Fr::rand(&mut verifier_rng));
+    assert!(MLSumcheck::verify_continuing(&poly.info(), asserted_sum + Fr::one(), &proof).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_prove_dry_run() {
+    let mut rng = test_rng();
+    let (poly, _) = random_instance::<Fr, _>(6, (2, 4), 3, &mut rng);
+    let profile = MLSumcheck::prove_dry_run(&poly).unwrap();
+    assert_eq!(profile.rounds, poly.num_variables);
+    assert_eq!(
+        profile.round_message_len,
+        poly.info().expected_round_message_len().unwrap()
+    );
+    assert!(MLSumcheck::prove_dry_run(&ListOfProductsOfPolynomials::<Fr>::new(6)).is_err());
+}
+
+#[test]
+fn test_represents_same() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let [a, b, c] = [(); 3].map(|_| Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)));
+    let coefficient = Fr::rand(&mut rng);
+
+    // a * b + a * c
+    let mut expanded = ListOfProductsOfPolynomials::new(nv);
+    expanded.add_product(vec![a.clone(), b.clone()], coefficient);
+    expanded.add_product(vec![a.clone(), c.clone()], coefficient);
+
+    // a * (b + c)
+    let b_plus_c = Rc::new(b.as_ref() + c.as_ref());
+    let mut factored = ListOfProductsOfPolynomials::new(nv);
+    factored.add_product(vec![a.clone(), b_plus_c], coefficient);
+    assert!(expanded.represents_same(&factored, &mut rng));
+    assert!(factored.represents_same(&expanded, &mut rng));
+
+    // a * (b + c) with a single wrong evaluation of b + c
+    let mut wrong_sum = b.as_ref() + c.as_ref();
+    wrong_sum.evaluations[3] += Fr::one();
+    let mut wrong = ListOfProductsOfPolynomials::new(nv);
+    wrong.add_product(vec![a, Rc::new(wrong_sum)], coefficient);
+    assert!(!expanded.represents_same(&wrong, &mut rng));
+
+    assert!(!expanded.represents_same(&ListOfProductsOfPolynomials::new(nv + 1), &mut rng));
+}
+
+#[test]
+fn test_empty_product_is_constant() {
+    let nv = 4;
+    let coefficient = Fr::from(7u64);
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product(Vec::new(), coefficient);
+    assert_eq!(poly.info().max_multiplicands, 1);
+    let asserted_sum = coefficient * Fr::from(1u64 << nv);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::extract_sum(&proof), asserted_sum);
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(subclaim.expected_evaluation, coefficient);
+    assert_eq!(poly.evaluate(&subclaim.point), coefficient);
+
+    // a constant next to a product of multiplicands
+    let mut rng = test_rng();
+    let (mut poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 2, &mut rng);
+    poly.add_product(Vec::new(), coefficient);
+    let asserted_sum = asserted_sum + coefficient * Fr::from(1u64 << nv);
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let subclaim = MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+
+    // the same constant through the oracle path
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let products = vec![(coefficient, vec![]), (Fr::one(), vec![0])];
+    let oracles: Vec<Box<dyn MultilinearOracle<Fr>>> = vec![Box::new(f.clone())];
+    let proof = MLSumcheck::prove_with_oracles(&products, oracles).unwrap();
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    poly.add_product(Vec::new(), coefficient);
+    poly.add_product(vec![Rc::new(f)], Fr::one());
+    let expected = MLSumcheck::prove(&poly).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+    let no_oracles: Vec<Box<dyn MultilinearOracle<Fr>>> = Vec::new();
+    assert!(MLSumcheck::prove_with_oracles(&[(coefficient, vec![])], no_oracles).is_err());
+}
+
+#[test]
+fn test_tolerate_extra_evaluations() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+
+    // re-prove with one more evaluation per round, at `max_multiplicands + 1`
+    let over_provisioned = |extra: Fr| {
+        let mut prover_state = IPForMLSumcheck::prover_init(&poly);
+        let mut fs_rng = Blake2s512Rng::setup();
+        let mut verifier_msg = None;
+        let mut evaluations = Vec::new();
+        for round in 0..info.num_variables {
+            let mut msg = IPForMLSumcheck::prove_round(&mut prover_state, &verifier_msg);
+            let next_point = Fr::from((info.max_multiplicands + 1) as u64);
+            let next = interpolate_and_evaluate(&msg.evaluations, next_point) + extra;
+            msg.evaluations.push(next);
+            if round == 0 {
+                fs_rng.feed_batch(&[&info, &msg]).unwrap();
+            } else {
+                fs_rng.feed(&msg).unwrap();
+            }
+            evaluations.push(msg);
+            verifier_msg = Some(IPForMLSumcheck::sample_round(&mut fs_rng));
+        }
+        evaluations
+    };
+    let tolerant = VerifyOptions {
+        tolerate_extra_evaluations: true,
+        ..VerifyOptions::default()
+    };
+    let cross_checking = VerifyOptions {
+        cross_check_extra_evaluations: true,
+        ..tolerant
+    };
+
+    let honest = over_provisioned(Fr::zero());
+    assert!(MLSumcheck::verify(&info, asserted_sum, &honest).is_err());
+    for options in [&tolerant, &cross_checking] {
+        let subclaim =
+            MLSumcheck::verify_with_options(&info, asserted_sum, &honest, options).unwrap();
+        assert_eq!(poly.evaluate(&subclaim.point), subclaim.expected_evaluation);
+        MLSumcheck::verify_with_options(&info, asserted_sum, &proof, options).unwrap();
+    }
+
+    let inconsistent = over_provisioned(Fr::one());
+    MLSumcheck::verify_with_options(&info, asserted_sum, &inconsistent, &tolerant).unwrap();
+    assert!(
+        MLSumcheck::verify_with_options(&info, asserted_sum, &inconsistent, &cross_checking)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_evaluate_random() {
+    let mut rng = test_rng();
+    // 3 * x0 * x1 + 2 * (1 - x0)
+    let x0_x1 = DenseMultilinearExtension::from_evaluations_vec(
+        2,
+        vec![Fr::zero(), Fr::zero(), Fr::zero(), Fr::one()],
+    );
+    let one_minus_x0 = DenseMultilinearExtension::from_evaluations_vec(
+        2,
+        vec![Fr::one(), Fr::zero(), Fr::one(), Fr::zero()],
+    );
+    let mut poly = ListOfProductsOfPolynomials::new(2);
+    poly.add_product(vec![Rc::new(x0_x1)], Fr::from(3u64));
+    poly.add_product(vec![Rc::new(one_minus_x0)], Fr::from(2u64));
+    assert_eq!(
+        poly.evaluate(&[Fr::from(2u64), Fr::from(5u64)]),
+        Fr::from(28u64)
+    );
+
+    let (point, value) = poly.evaluate_random(&mut rng);
+    assert_eq!(point.len(), 2);
+    let expected = Fr::from(3u64) * point[0] * point[1] + Fr::from(2u64) * (Fr::one() - point[0]);
+    assert_eq!(value, expected);
+    assert!(!value.is_zero());
+}
+
+#[test]
+fn test_prove_shifted() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    for shift in [1, 4, 6, 31, 32] {
+        let rotated = DenseMultilinearExtension::from_evaluations_vec(
+            nv,
+            (0..1 << nv)
+                .map(|i| f.evaluations[(i + shift) % (1 << nv)])
+                .collect(),
+        );
+        let mut poly = ListOfProductsOfPolynomials::new(nv);
+        poly.add_product(vec![Rc::new(f.clone()), Rc::new(rotated)], Fr::one());
+        let expected = MLSumcheck::prove(&poly).unwrap();
+
+        let oracles: Vec<Box<dyn MultilinearOracle<Fr>>> = vec![
+            Box::new(f.clone()),
+            Box::new(Shifted::new(f.clone(), shift)),
+        ];
+        let proof = MLSumcheck::prove_with_oracles(&[(Fr::one(), vec![0, 1])], oracles).unwrap();
+        assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+        let asserted_sum: Fr = (0..1 << nv)
+            .map(|i| f.evaluations[i] * f.evaluations[(i + shift) % (1 << nv)])
+            .sum();
+        MLSumcheck::verify(&poly.info(), asserted_sum, &proof).unwrap();
+    }
+
+    let narrow = DenseMultilinearExtension::<Fr>::rand(nv - 1, &mut rng);
+    let oracles: Vec<Box<dyn MultilinearOracle<Fr>>> =
+        vec![Box::new(f), Box::new(Shifted::new(narrow, 1))];
+    assert!(MLSumcheck::prove_with_oracles(&[(Fr::one(), vec![0, 1])], oracles).is_err());
+}
+
+#[test]
+fn test_degree_overflow() {
+    let mut rng = test_rng();
+    let nv = 3;
+    let f = DenseMultilinearExtension::<Fr>::rand(nv, &mut rng);
+    let mut poly = ListOfProductsOfPolynomials::new(nv);
+    // rejected before allocating the references to `f`
+    for k in [
+        ListOfProductsOfPolynomials::<Fr>::MAX_POWER + 1,
+        usize::MAX / 2,
+        usize::MAX - 1,
+        usize::MAX,
+    ] {
+        assert!(matches!(
+            poly.try_add_power(Fr::one(), &f, k),
+            Err(crate::Error::DegreeOverflow)
+        ));
+    }
+    assert_eq!(poly.max_multiplicands, 0);
+    assert!(poly.products.is_empty());
+    assert!(poly.flattened_ml_extensions.is_empty());
+
+    poly.try_add_power(Fr::one(), &f, 3).unwrap();
+    assert_eq!(poly.info().expected_round_message_len().unwrap(), 4);
+
+    // the fields are public, and a deserialized info can have any degree
+    let huge = PolynomialInfo {
+        max_multiplicands: usize::MAX,
+        num_variables: nv,
+    };
+    assert!(matches!(
+        huge.expected_round_message_len(),
+        Err(crate::Error::DegreeOverflow)
+    ));
+    for max_multiplicands in [usize::MAX - 1, usize::MAX / 4] {
+        let info = PolynomialInfo {
+            max_multiplicands,
+            num_variables: nv,
+        };
+        assert!(matches!(
+            info.recursion_constraint_estimate(),
+            Err(crate::Error::DegreeOverflow)
+        ));
+    }
+    poly.max_multiplicands = usize::MAX - 1;
+    poly.add_product(vec![Rc::new(f.clone()), Rc::new(f)], Fr::one());
+    assert!(matches!(
+        MLSumcheck::prove(&poly),
+        Err(crate::Error::DegreeOverflow)
+    ));
+}
+
+#[test]
+fn test_verification_certificate() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let (subclaim, certificate) =
+        MLSumcheck::verify_with_certificate(&info, asserted_sum, &proof).unwrap();
+    assert_eq!(certificate.subclaim_point, subclaim.point);
+    assert_eq!(
+        certificate.expected_evaluation,
+        subclaim.expected_evaluation
+    );
+    assert!(certificate.check(&proof, &info));
+
+    let mut bytes = Vec::new();
+    certificate.serialize_compressed(&mut bytes).unwrap();
+    let decoded = VerificationCertificate::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+    assert_eq!(decoded, certificate);
+
+    // the last round still sums to the expected value, so the tampered proof verifies, but to
+    // another subclaim
+    let mut tampered = proof.clone();
+    tampered[4].evaluations[0] += Fr::one();
+    tampered[4].evaluations[1] -= Fr::one();
+    let (_, tampered_certificate) =
+        MLSumcheck::verify_with_certificate(&info, asserted_sum, &tampered).unwrap();
+    assert!(!certificate.check(&tampered, &info));
+    assert!(!tampered_certificate.check(&proof, &info));
+    assert!(tampered_certificate.check(&tampered, &info));
+
+    let mut wrong_sum = certificate.clone();
+    wrong_sum.claimed_sum += Fr::one();
+    assert!(!wrong_sum.check(&proof, &info));
+    let mut wrong_digest = certificate;
+    wrong_digest.transcript_digest[0] ^= 1;
+    assert!(!wrong_digest.check(&proof, &info));
+}
+
+#[test]
+fn test_verify_structural() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let (poly, asserted_sum) = random_instance::<Fr, _>(nv, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let subclaim = MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+    MLSumcheck::verify_structural(&info, asserted_sum, &proof, &subclaim.point).unwrap();
+
+    // the relations do not depend on how the challenges were derived
+    let point: Vec<Fr> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    let (proof, _) =
+        MLSumcheck::prove_with_challenges(&poly, &mut BatchChallengeSource::new(point.clone()))
+            .unwrap();
+    MLSumcheck::verify_structural(&info, asserted_sum, &proof, &point).unwrap();
+
+    let other: Vec<Fr> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+    assert!(matches!(
+        MLSumcheck::verify_structural(&info, asserted_sum, &proof, &other),
+        Err(crate::Error::RoundCheckFailed(_))
+    ));
+    assert!(
+        MLSumcheck::verify_structural(&info, asserted_sum + Fr::one(), &proof, &point).is_err()
+    );
+    assert!(MLSumcheck::verify_structural(&info, asserted_sum, &proof, &point[1..]).is_err());
+}
+
+#[test]
+fn test_prove_self_scaling() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let tables: Vec<Rc<DenseMultilinearExtension<Fr>>> = (0..4)
+        .map(|_| Rc::new(DenseMultilinearExtension::rand(nv, &mut rng)))
+        .collect();
+    let shape = [
+        (0, vec![0, 1]),
+        (2, vec![1, 2, 3]),
+        (2, vec![0]),
+        (3, vec![3, 3]),
+    ];
+    let mut symbolic = ListOfProductsOfPolynomials::new_with_coefficients(nv);
+    for (k, product) in &shape {
+        symbolic.add_product(
+            product.iter().map(|&i| tables[i].clone()),
+            ChallengePower(*k),
+        );
+    }
+    let (proof, sums, rho) = MLSumcheck::prove_self_scaling(&symbolic).unwrap();
+    assert_eq!(sums.len(), 4);
+    assert_eq!(sums[1], Fr::zero());
+
+    // the same polynomial with the coefficients computed eagerly
+    let mut eager = ListOfProductsOfPolynomials::new(nv);
+    for (k, product) in &shape {
+        eager.add_product(
+            product.iter().map(|&i| tables[i].clone()),
+            rho.pow([*k as u64]),
+        );
+    }
+    let mut fs_rng = Blake2s512Rng::setup();
+    fs_rng.feed(&eager.info()).unwrap();
+    fs_rng.feed(&sums).unwrap();
+    assert_eq!(Fr::rand(&mut fs_rng), rho);
+    let (expected, _) = MLSumcheck::prove_as_subprotocol(&mut fs_rng, &eager).unwrap();
+    assert_eq!(MLSumcheck::first_divergence(&proof, &expected), None);
+
+    let (subclaim, verifier_rho) =
+        MLSumcheck::verify_self_scaling(&symbolic.info(), &sums, &proof).unwrap();
+    assert_eq!(verifier_rho, rho);
+    assert_eq!(
+        eager.evaluate(&subclaim.point),
+        subclaim.expected_evaluation
+    );
+
+    let mut wrong_sums = sums;
+    wrong_sums[0] += Fr::one();
+    assert!(MLSumcheck::verify_self_scaling(&symbolic.info(), &wrong_sums, &proof).is_err());
+
+    // a huge power is rejected before the sums are allocated
+    let mut huge = ListOfProductsOfPolynomials::new_with_coefficients(nv);
+    huge.add_product(vec![tables[0].clone()], ChallengePower(usize::MAX));
+    assert!(matches!(
+        MLSumcheck::prove_self_scaling(&huge),
+        Err(crate::Error::DegreeOverflow)
+    ));
+}
+
+#[test]
+fn test_verify_against_merkle_root() {
+    let mut rng = test_rng();
+    let (poly, asserted_sum) = random_instance::<Fr, _>(5, (2, 4), 3, &mut rng);
+    let info = poly.info();
+    let proof = MLSumcheck::prove(&poly).unwrap();
+    let root = merkle_root(&proof);
+    let openings = merkle_openings(&proof);
+    // 5 rounds are padded to 8 leaves
+    assert!(openings.iter().all(|opening| opening.path.len() == 3));
+    assert!(openings[3].verify(3, &root));
+    assert!(!openings[3].verify(2, &root));
+
+    let subclaim =
+        MLSumcheck::verify_against_merkle_root(&info, asserted_sum, &root, &openings).unwrap();
+    let expected = MLSumcheck::verify(&info, asserted_sum, &proof).unwrap();
+    assert_eq!(subclaim.point, expected.point);
+    assert_eq!(subclaim.expected_evaluation, expected.expected_evaluation);
+
+    let mut swapped = openings.clone();
+    swapped[1].message = openings[2].message.clone();
+    assert!(matches!(
+        MLSumcheck::verify_against_merkle_root(&info, asserted_sum, &root, &swapped),
+        Err(crate::Error::Reject(_))
+    ));
+    let mut other_root = root;
+    other_root[0] ^= 1;
+    assert!(matches!(
+        MLSumcheck::verify_against_merkle_root(&info, asserted_sum, &other_root, &openings),
+        Err(crate::Error::Reject(_))
+    ));
+    assert!(matches!(
+        MLSumcheck::verify_against_merkle_root(&info, asserted_sum, &root, &openings[..4]),
+        Err(crate::Error::WrongProofLength {
+            expected: 5,
+            got: 4
+        })
+    ));
+    assert!(MLSumcheck::verify_against_merkle_root(
+        &info,
+        asserted_sum + Fr::one(),
+        &root,
+        &openings
+    )
+    .is_err());
+}
+
+#[test]
+fn test_raised_degree_builders() {
+    let mut rng = test_rng();
+    let (mut poly, asserted_sum) = random_instance::<Fr, _>(4, (2, 3), 2, &mut rng);
+    poly.raise_degree(5);
+    let info = poly.info();
+    assert_eq!(info.max_multiplicands, 5);
+
+    let mut merged = ListOfProductsOfPolynomials::new(4);
+    merged.merge(&poly);
+    assert_eq!(merged.max_multiplicands, 5);
+    let mut scaled = ListOfProductsOfPolynomials::new(4);
+    scaled.add_scaled(Fr::from(2u64), &poly).unwrap();
+    assert_eq!(scaled.max_multiplicands, 5);
+
+    // `prove_rlc` merges the raised polynomial
+    let (other, other_sum) = random_instance::<Fr, _>(4, (2, 3), 2, &mut rng);
+    let (proof, _) = MLSumcheck::prove_rlc(&[&poly, &other], &mut Blake2s512Rng::setup()).unwrap();
+    MLSumcheck::verify_rlc(
+        &[info.clone(), other.info()],
+        &[asserted_sum, other_sum],
+        &proof,
+        &mut Blake2s512Rng::setup(),
+    )
+    .expect("raised rlc proof should verify");
+
+    // `prove_over_domain` scales the domain of the raised polynomial
+    let shift = Fr::from(3u64);
+    let (proof, _) =
+        MLSumcheck::prove_over_domain(&mut Blake2s512Rng::setup(), &poly, shift).unwrap();
+    MLSumcheck::verify_over_domain(
+        &mut Blake2s512Rng::setup(),
+        &info,
+        shift,
+        MLSumcheck::extract_sum(&proof),
+        &proof,
+    )
+    .expect("raised proof over a domain should verify");
+
+    // `prove_even` restricts a variable of the raised polynomial
+    let proof = MLSumcheck::prove_even(1, &poly).unwrap();
+    MLSumcheck::verify_even(1, &info, MLSumcheck::extract_sum(&proof), &proof)
+        .expect("raised even proof should verify");
+
+    // `prove_with_permutation` permutes the variables of the raised polynomial
+    let perm = [2, 0, 3, 1];
+    let (proof, _) =
+        MLSumcheck::prove_with_permutation(&poly, &perm, &mut Blake2s512Rng::setup()).unwrap();
+    MLSumcheck::verify_with_permutation(
+        &info,
+        asserted_sum,
+        &proof,
+        &perm,
+        &mut Blake2s512Rng::setup(),
+    )
+    .expect("raised permuted proof should verify");
+}
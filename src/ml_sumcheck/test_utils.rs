@@ -0,0 +1,80 @@
+//! Helpers to build random sumcheck instances for tests and benchmarks.
+
+use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::rand::Rng;
+use ark_std::rc::Rc;
+use ark_std::vec::Vec;
+
+/// Returns a random polynomial in `num_vars` variables and its sum over the boolean hypercube.
+///
+/// The polynomial has `num_products` products with random coefficients, each with a number of
+/// random multiplicands drawn from `num_multiplicands_range.0..num_multiplicands_range.1`. The sum
+/// is computed exactly from the evaluation tables. Panics if the range is empty.
+pub fn random_instance<F: Field, R: Rng>(
+    num_vars: usize,
+    num_multiplicands_range: (usize, usize),
+    num_products: usize,
+    rng: &mut R,
+) -> (ListOfProductsOfPolynomials<F>, F) {
+    let mut sum = F::zero();
+    let mut polynomial = ListOfProductsOfPolynomials::new(num_vars);
+    for _ in 0..num_products {
+        let num_multiplicands = rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
+        let (product, product_sum) = random_product(num_vars, num_multiplicands, rng);
+        let coefficient = F::rand(rng);
+        polynomial.add_product(product.into_iter(), coefficient);
+        sum += product_sum * coefficient;
+    }
+    (polynomial, sum)
+}
+
+/// Returns `num_multiplicands` random multiplicands in `num_vars` variables, and the sum of their
+/// product over the boolean hypercube.
+fn random_product<F: Field, R: Rng>(
+    num_vars: usize,
+    num_multiplicands: usize,
+    rng: &mut R,
+) -> (Vec<Rc<DenseMultilinearExtension<F>>>, F) {
+    let mut multiplicands = vec![Vec::with_capacity(1 << num_vars); num_multiplicands];
+    let mut sum = F::zero();
+    for _ in 0..(1 << num_vars) {
+        let mut product = F::one();
+        for multiplicand in &mut multiplicands {
+            let val = F::rand(rng);
+            multiplicand.push(val);
+            product *= val;
+        }
+        sum += product;
+    }
+    (
+        multiplicands
+            .into_iter()
+            .map(|x| Rc::new(DenseMultilinearExtension::from_evaluations_vec(num_vars, x)))
+            .collect(),
+        sum,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ml_sumcheck::protocol::IPForMLSumcheck;
+    use crate::ml_sumcheck::test_utils::random_instance;
+    use crate::ml_sumcheck::MLSumcheck;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_random_instance() {
+        let mut rng = test_rng();
+        let (polynomial, sum) = random_instance::<Fr, _>(6, (1, 4), 4, &mut rng);
+        assert_eq!(polynomial.num_products(), 4);
+        assert!(polynomial.max_multiplicands <= 3);
+        assert_eq!(IPForMLSumcheck::prover_init(&polynomial).claimed_sum(), sum);
+
+        let proof = MLSumcheck::prove(&polynomial).expect("fail to prove");
+        let subclaim = MLSumcheck::verify(&polynomial.info(), sum, &proof).expect("fail to verify");
+        assert!(polynomial.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+    }
+}
@@ -0,0 +1,269 @@
+//! JSON test vectors for conformance testing against other sumcheck implementations.
+//!
+//! A test vector is a JSON object of the form
+//!
+//! ```text
+//! {
+//!     "num_variables": 2,
+//!     "max_multiplicands": 2,
+//!     "products": [
+//!         { "coefficient": "0x3", "multiplicands": [["0x1", "0x2", "0x3", "0x4"], ...] },
+//!         ...
+//!     ],
+//!     "claimed_sum": "0x126",
+//!     "proof": [["0x63", "0xc3", "0x12f"], ...]
+//! }
+//! ```
+//!
+//! Field elements are strings holding their canonical integer in big-endian hexadecimal, with a
+//! `0x` prefix. Each multiplicand is given by its full evaluation table of `2^num_variables`
+//! entries, where bit `i` of the index is the value of variable `i`. `proof` holds one list of
+//! evaluations `P(0), P(1), ..., P(max_multiplicands)` per round, as produced by `MLSumcheck::prove`
+//! with a fresh `Blake2s512Rng` transcript.
+
+use crate::ml_sumcheck::data_structures::{ListOfProductsOfPolynomials, PolynomialInfo};
+use crate::ml_sumcheck::protocol::prover::ProverMsg;
+use crate::ml_sumcheck::{MLSumcheck, Proof};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::string::{String, ToString};
+use ark_std::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// A sumcheck instance together with its expected sum and proof.
+pub struct TestVector<F: PrimeField> {
+    /// number of variables of the polynomial
+    pub num_variables: usize,
+    /// max number of multiplicands in each product
+    pub max_multiplicands: usize,
+    /// products of the polynomial, as a coefficient and the evaluation tables of its multiplicands
+    pub products: Vec<(F, Vec<Vec<F>>)>,
+    /// sum of the polynomial over the boolean hypercube
+    pub claimed_sum: F,
+    /// expected proof
+    pub proof: Proof<F>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawProduct {
+    coefficient: String,
+    multiplicands: Vec<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawVector {
+    num_variables: usize,
+    max_multiplicands: usize,
+    products: Vec<RawProduct>,
+    claimed_sum: String,
+    proof: Vec<Vec<String>>,
+}
+
+/// Parse a test vector from its JSON encoding.
+///
+/// Returns `Error::SerializationError` if `json` does not follow the schema, or if a field element
+/// is not a canonical hexadecimal integer.
+pub fn load_vector<F: PrimeField>(json: &str) -> Result<TestVector<F>, crate::Error> {
+    let raw: RawVector =
+        serde_json::from_str(json).map_err(|_| crate::Error::SerializationError)?;
+    let products = raw
+        .products
+        .iter()
+        .map(|product| {
+            let coefficient = parse_element(&product.coefficient)?;
+            let multiplicands = product
+                .multiplicands
+                .iter()
+                .map(|table| parse_elements(table))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((coefficient, multiplicands))
+        })
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+    let proof = raw
+        .proof
+        .iter()
+        .map(|round| {
+            Ok(ProverMsg {
+                evaluations: parse_elements(round)?,
+            })
+        })
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+    Ok(TestVector {
+        num_variables: raw.num_variables,
+        max_multiplicands: raw.max_multiplicands,
+        products,
+        claimed_sum: parse_element(&raw.claimed_sum)?,
+        proof,
+    })
+}
+
+/// Encode a test vector as JSON.
+pub fn to_json<F: PrimeField>(vector: &TestVector<F>) -> String {
+    let raw = RawVector {
+        num_variables: vector.num_variables,
+        max_multiplicands: vector.max_multiplicands,
+        products: vector
+            .products
+            .iter()
+            .map(|(coefficient, multiplicands)| RawProduct {
+                coefficient: format_element(coefficient),
+                multiplicands: multiplicands
+                    .iter()
+                    .map(|table| table.iter().map(format_element).collect())
+                    .collect(),
+            })
+            .collect(),
+        claimed_sum: format_element(&vector.claimed_sum),
+        proof: vector
+            .proof
+            .iter()
+            .map(|msg| msg.evaluations.iter().map(format_element).collect())
+            .collect(),
+    };
+    serde_json::to_string_pretty(&raw).expect("test vectors are always serializable")
+}
+
+/// Check a test vector against this crate.
+///
+/// The polynomial must match `num_variables` and `max_multiplicands`, sum to `claimed_sum`, and prove
+/// to exactly `proof`. The proof must verify, and its subclaim must hold for the polynomial.
+pub fn run_vector<F: PrimeField>(vector: &TestVector<F>) -> Result<(), crate::Error> {
+    if vector.num_variables >= usize::BITS as usize {
+        return Err(crate::Error::SerializationError);
+    }
+    let num_evaluations = 1 << vector.num_variables;
+    let mut polynomial = ListOfProductsOfPolynomials::new(vector.num_variables);
+    for (coefficient, multiplicands) in &vector.products {
+        if multiplicands.is_empty() || multiplicands.iter().any(|t| t.len() != num_evaluations) {
+            return Err(crate::Error::SerializationError);
+        }
+        polynomial.add_product_padded(*coefficient, multiplicands.clone());
+    }
+    let expected_info = PolynomialInfo {
+        max_multiplicands: vector.max_multiplicands,
+        num_variables: vector.num_variables,
+    };
+    let info = polynomial.info();
+    if info.max_multiplicands != expected_info.max_multiplicands
+        || info.num_variables != expected_info.num_variables
+    {
+        return Err(reject("polynomial does not match the vector's info"));
+    }
+    let proof = MLSumcheck::prove(&polynomial)?;
+    if MLSumcheck::try_extract_sum(&proof)? != vector.claimed_sum {
+        return Err(reject("claimed sum does not match the polynomial"));
+    }
+    let same_proof = proof.len() == vector.proof.len()
+        && proof
+            .iter()
+            .zip(&vector.proof)
+            .all(|(ours, theirs)| ours.evaluations == theirs.evaluations);
+    if !same_proof {
+        return Err(reject("proof does not match the vector"));
+    }
+    let subclaim = MLSumcheck::verify(&expected_info, vector.claimed_sum, &vector.proof)?;
+    if polynomial.evaluate(&subclaim.point) != subclaim.expected_evaluation {
+        return Err(reject("subclaim does not hold for the polynomial"));
+    }
+    Ok(())
+}
+
+fn reject(reason: &str) -> crate::Error {
+    crate::Error::Reject(Some(reason.to_string()))
+}
+
+fn parse_elements<F: PrimeField>(elements: &[String]) -> Result<Vec<F>, crate::Error> {
+    elements.iter().map(|e| parse_element(e)).collect()
+}
+
+fn parse_element<F: PrimeField>(s: &str) -> Result<F, crate::Error> {
+    let digits = s
+        .strip_prefix("0x")
+        .filter(|digits| !digits.is_empty())
+        .ok_or(crate::Error::SerializationError)?;
+    let nibbles = digits
+        .chars()
+        .rev()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(crate::Error::SerializationError)?;
+    let bytes_le: Vec<u8> = nibbles
+        .chunks(2)
+        .map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4))
+        .collect();
+    let element = F::from_le_bytes_mod_order(&bytes_le);
+    // reject integers not smaller than the modulus
+    let mut canonical = element.into_bigint().to_bytes_le();
+    canonical.resize(bytes_le.len().max(canonical.len()), 0);
+    let mut padded = bytes_le;
+    padded.resize(canonical.len(), 0);
+    if canonical != padded {
+        return Err(crate::Error::SerializationError);
+    }
+    Ok(element)
+}
+
+fn format_element<F: PrimeField>(element: &F) -> String {
+    let bytes = element.into_bigint().to_bytes_be();
+    let mut s = String::from("0x");
+    let mut digits = bytes.iter().skip_while(|b| **b == 0).peekable();
+    match digits.next() {
+        None => s.push('0'),
+        Some(first) => {
+            s.push_str(&format!("{first:x}"));
+            for b in digits {
+                s.push_str(&format!("{b:02x}"));
+            }
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ml_sumcheck::test_vectors::{load_vector, run_vector, to_json};
+    use ark_test_curves::bls12_381::Fr;
+
+    /// `3 * A * B + 7 * C` over two variables
+    const SAMPLE_VECTOR: &str = r#"{
+    "num_variables": 2,
+    "max_multiplicands": 2,
+    "products": [
+        {
+            "coefficient": "0x3",
+            "multiplicands": [["0x1", "0x2", "0x3", "0x4"], ["0x5", "0x6", "0x7", "0x8"]]
+        },
+        {
+            "coefficient": "0x7",
+            "multiplicands": [["0x2", "0x0", "0x1", "0x9"]]
+        }
+    ],
+    "claimed_sum": "0x126",
+    "proof": [
+        ["0x63", "0xc3", "0x12f"],
+        [
+            "0x1045b0682f1e33129e43d083340731a8e65936df909f34952cba79f55b2ae369",
+            "0x53838e73cd647f762575b11fe55cac1aed7a4f53d27c5f7e92adf782a4d93739",
+            "0x22d3c52c420d4e91796db9b48d104e87a0ddc3c5145b2e68f8a17510ee878b20"
+        ]
+    ]
+}"#;
+
+    #[test]
+    fn test_sample_vector() {
+        let vector = load_vector::<Fr>(SAMPLE_VECTOR).unwrap();
+        run_vector(&vector).expect("sample vector should verify");
+
+        let reloaded = load_vector::<Fr>(&to_json(&vector)).unwrap();
+        assert_eq!(to_json(&reloaded), to_json(&vector));
+        run_vector(&reloaded).expect("round-tripped vector should verify");
+
+        let tampered = SAMPLE_VECTOR.replace("0x126", "0x127");
+        assert!(run_vector(&load_vector::<Fr>(&tampered).unwrap()).is_err());
+        let non_canonical = SAMPLE_VECTOR.replace(
+            "\"0x3\"",
+            "\"0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000004\"",
+        );
+        assert!(load_vector::<Fr>(&non_canonical).is_err());
+        assert!(load_vector::<Fr>("{}").is_err());
+    }
+}
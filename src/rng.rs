@@ -1,8 +1,14 @@
 //! Fiat-Shamir Random Generator
-use ark_serialize::CanonicalSerialize;
+use ark_ff::Field;
+use ark_serialize::{CanonicalSerialize, Compress, SerializationError, Write};
 use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 use blake2::{Blake2s, Digest};
+#[cfg(feature = "sponge")]
+use {
+    ark_crypto_primitives::sponge::{Absorb, CryptographicSponge},
+    ark_std::marker::PhantomData,
+};
 // use merlin::{TranscriptRng, Transcript};
 /// Random Field Element Generator where randomness `feed` adds entropy for the output.
 ///
@@ -12,27 +18,192 @@ use blake2::{Blake2s, Digest};
 pub trait FeedableRNG: RngCore {
     /// Error type
     type Error: ark_std::error::Error + From<crate::Error>;
-    /// Setup should not have any parameter.
-    fn setup() -> Self;
 
     /// Provide randomness for the generator, given the message.
     fn feed<M: CanonicalSerialize>(&mut self, msg: &M) -> Result<(), Self::Error>;
+
+    /// Provide randomness for the generator, given several messages with no sampling in between.
+    ///
+    /// The default feeds the messages one by one. Generators for which each feed is costly, such as
+    /// sponges, can override this to absorb all messages at once. An override changes the
+    /// transcript of the generator, and therefore every proof made with it.
+    fn feed_batch(&mut self, items: &[&dyn AbsorbItem]) -> Result<(), Self::Error> {
+        for item in items {
+            self.feed(&Absorbed(*item))?;
+        }
+        Ok(())
+    }
+
+    /// Derive a child transcript from the current state.
+    ///
+    /// The child starts from the same state as `self`, but later feeds to either one do not affect
+    /// the other. This is meant for nesting a subprotocol: run the inner protocol on the fork, then
+    /// merge it back explicitly by feeding its messages (or any binding of them) to the outer transcript.
+    fn fork(&self) -> Self
+    where
+        Self: Clone,
+    {
+        self.clone()
+    }
+}
+
+/// A `FeedableRNG` that can be created from nothing.
+///
+/// This is not part of `FeedableRNG`, since a borrowed generator `&mut R` is a `FeedableRNG` but
+/// cannot be created: code that needs a fresh generator asks for `SetupRNG`, and is then not
+/// given a borrowed one.
+pub trait SetupRNG: FeedableRNG {
+    /// Setup should not have any parameter.
+    fn setup() -> Self;
+}
+
+/// Object-safe view of a `FeedableRNG`, so that a transcript can be stored as a trait object.
+///
+/// This is implemented for every `FeedableRNG`. A `&mut R` with `R: DynFeedableRNG + ?Sized`, such
+/// as `&mut dyn DynFeedableRNG<Error = crate::Error>`, is itself a `FeedableRNG`, so it can be given to
+/// the subprotocol functions. Feeding through the reference is the same as feeding `R` directly.
+pub trait DynFeedableRNG: RngCore {
+    /// Error type
+    type Error: ark_std::error::Error + From<crate::Error>;
+
+    /// Provide randomness for the generator, given several messages. See `FeedableRNG::feed_batch`.
+    fn feed_items(&mut self, items: &[&dyn AbsorbItem]) -> Result<(), Self::Error>;
+}
+
+impl<R: FeedableRNG> DynFeedableRNG for R {
+    type Error = R::Error;
+
+    fn feed_items(&mut self, items: &[&dyn AbsorbItem]) -> Result<(), Self::Error> {
+        self.feed_batch(items)
+    }
+}
+
+impl<R: DynFeedableRNG + ?Sized> FeedableRNG for &mut R {
+    type Error = R::Error;
+
+    fn feed<M: CanonicalSerialize>(&mut self, msg: &M) -> Result<(), Self::Error> {
+        (**self).feed_items(&[msg])
+    }
+
+    fn feed_batch(&mut self, items: &[&dyn AbsorbItem]) -> Result<(), Self::Error> {
+        (**self).feed_items(items)
+    }
+}
+
+/// Transcript held by a prover.
+///
+/// This only wraps a `FeedableRNG`, so that functions taking the prover side of a transcript cannot be
+/// given the transcript of a verifier by mistake.
+#[repr(transparent)]
+pub struct ProverTranscript<R: FeedableRNG>(R);
+
+/// Transcript held by a verifier. See `ProverTranscript`.
+#[repr(transparent)]
+pub struct VerifierTranscript<R: FeedableRNG>(R);
+
+impl<R: FeedableRNG> ProverTranscript<R> {
+    /// Use `rng` as the prover transcript.
+    pub fn new(rng: R) -> Self {
+        Self(rng)
+    }
+
+    /// Returns the underlying generator.
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+
+    /// Access the underlying generator.
+    pub fn as_rng_mut(&mut self) -> &mut R {
+        &mut self.0
+    }
+}
+
+impl<R: FeedableRNG> VerifierTranscript<R> {
+    /// Use `rng` as the verifier transcript.
+    pub fn new(rng: R) -> Self {
+        Self(rng)
+    }
+
+    /// Returns the underlying generator.
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+
+    /// Access the underlying generator.
+    pub fn as_rng_mut(&mut self) -> &mut R {
+        &mut self.0
+    }
+}
+
+impl<R: SetupRNG> ProverTranscript<R> {
+    /// Create a prover transcript by `SetupRNG::setup`.
+    pub fn setup() -> Self {
+        Self(R::setup())
+    }
+}
+
+impl<R: SetupRNG> VerifierTranscript<R> {
+    /// Create a verifier transcript by `SetupRNG::setup`.
+    pub fn setup() -> Self {
+        Self(R::setup())
+    }
+}
+
+/// A message that can be given to `FeedableRNG::feed_batch`.
+///
+/// This is implemented for every `CanonicalSerialize` type.
+pub trait AbsorbItem {
+    /// write the uncompressed serialization of the message to `writer`
+    fn write_uncompressed(&self, writer: &mut dyn Write) -> Result<(), SerializationError>;
+
+    /// size of the uncompressed serialization of the message
+    fn absorbed_size(&self) -> usize;
+}
+
+impl<T: CanonicalSerialize> AbsorbItem for T {
+    fn write_uncompressed(&self, writer: &mut dyn Write) -> Result<(), SerializationError> {
+        self.serialize_uncompressed(writer)
+    }
+
+    fn absorbed_size(&self) -> usize {
+        self.uncompressed_size()
+    }
+}
+
+/// Serializes an `AbsorbItem` the same way as the underlying message is serialized by `feed`.
+struct Absorbed<'a>(&'a dyn AbsorbItem);
+
+impl CanonicalSerialize for Absorbed<'_> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.write_uncompressed(&mut writer)
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.0.absorbed_size()
+    }
 }
 
 /// 512-bits digest hash pseudorandom generator
+#[derive(Clone)]
 pub struct Blake2s512Rng {
     /// current digest instance
     current_digest: Blake2s,
 }
 
-impl FeedableRNG for Blake2s512Rng {
-    type Error = crate::Error;
-
+impl SetupRNG for Blake2s512Rng {
     fn setup() -> Self {
         Self {
             current_digest: Blake2s::new(),
         }
     }
+}
+
+impl FeedableRNG for Blake2s512Rng {
+    type Error = crate::Error;
 
     fn feed<M: CanonicalSerialize>(&mut self, msg: &M) -> Result<(), Self::Error> {
         let mut buf = Vec::new();
@@ -81,6 +252,187 @@ impl RngCore for Blake2s512Rng {
     }
 }
 
+/// Provides the parameters of the sponge created by `SpongeRng::setup`.
+#[cfg(feature = "sponge")]
+pub trait SpongeConfig<S: CryptographicSponge> {
+    /// parameters of the sponge
+    fn config() -> S::Config;
+}
+
+/// Pseudorandom generator backed by any `CryptographicSponge`, e.g. Poseidon.
+///
+/// `feed` absorbs the uncompressed serialization of the message, and randomness is squeezed from the sponge.
+/// The sponge parameters used by `setup` are given by `C`.
+#[cfg(feature = "sponge")]
+pub struct SpongeRng<S: CryptographicSponge, C: SpongeConfig<S>> {
+    sponge: S,
+    _config: PhantomData<C>,
+}
+
+#[cfg(feature = "sponge")]
+impl<S: CryptographicSponge, C: SpongeConfig<S>> SpongeRng<S, C> {
+    /// Wraps an already initialized sponge.
+    pub fn from_sponge(sponge: S) -> Self {
+        Self {
+            sponge,
+            _config: PhantomData,
+        }
+    }
+
+    /// same as `feed`, but first absorbs `label` as arkworks' `CryptographicSponge::fork` does: the
+    /// length of the label, then its bytes
+    ///
+    /// This keeps the transcript in line with arkworks subprotocols that separate their absorbs by
+    /// label.
+    pub fn absorb_with_label<M: CanonicalSerialize>(
+        &mut self,
+        label: &[u8],
+        msg: &M,
+    ) -> Result<(), crate::Error> {
+        let mut prefix = label.len().to_sponge_bytes_as_vec();
+        prefix.extend_from_slice(label);
+        self.sponge.absorb(&prefix);
+        self.feed(msg)
+    }
+}
+
+#[cfg(feature = "sponge")]
+impl<S: CryptographicSponge, C: SpongeConfig<S>> Clone for SpongeRng<S, C> {
+    fn clone(&self) -> Self {
+        Self::from_sponge(self.sponge.clone())
+    }
+}
+
+#[cfg(feature = "sponge")]
+impl<S: CryptographicSponge, C: SpongeConfig<S>> SetupRNG for SpongeRng<S, C> {
+    fn setup() -> Self {
+        Self::from_sponge(S::new(&C::config()))
+    }
+}
+
+#[cfg(feature = "sponge")]
+impl<S: CryptographicSponge, C: SpongeConfig<S>> FeedableRNG for SpongeRng<S, C> {
+    type Error = crate::Error;
+
+    fn feed<M: CanonicalSerialize>(&mut self, msg: &M) -> Result<(), Self::Error> {
+        let mut buf = Vec::new();
+        msg.serialize_uncompressed(&mut buf)?;
+        self.sponge.absorb(&buf);
+        Ok(())
+    }
+
+    /// Absorbs the concatenated serializations of all messages at once.
+    ///
+    /// This changes the transcript: the sponge is in a different state than after feeding the
+    /// messages one by one, so the challenges differ, and proofs made with `SpongeRng` before
+    /// `feed_batch` was used by the prover and the verifier no longer verify.
+    fn feed_batch(&mut self, items: &[&dyn AbsorbItem]) -> Result<(), Self::Error> {
+        let mut buf = Vec::new();
+        for item in items {
+            item.write_uncompressed(&mut buf)?;
+        }
+        self.sponge.absorb(&buf);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sponge")]
+impl<S: CryptographicSponge, C: SpongeConfig<S>> RngCore for SpongeRng<S, C> {
+    fn next_u32(&mut self) -> u32 {
+        let mut temp = [0u8; 4];
+        self.fill_bytes(&mut temp);
+        u32::from_le_bytes(temp)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut temp = [0u8; 8];
+        self.fill_bytes(&mut temp);
+        u64::from_le_bytes(temp)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let output = self.sponge.squeeze_bytes(dest.len());
+        dest.copy_from_slice(&output);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Source of the verifier challenges of a sumcheck, one per round.
+///
+/// `MLSumcheck::prove_with_challenges` and `MLSumcheck::verify_with_challenges` take their
+/// challenges from a `ChallengeSource` instead of deriving each one from the transcript after the
+/// prover message of its round.
+pub trait ChallengeSource<F: Field> {
+    /// challenge of the next round
+    fn next(&mut self) -> F;
+}
+
+/// Challenges fixed in advance, e.g. all derived at once from a single vector commitment.
+///
+/// Sumcheck is only sound if the challenge of each round is unpredictable to the prover when it
+/// sends the message of that round. With a batch of challenges, this holds only if the prover is
+/// bound to all of its messages before the batch is derived, e.g. because the batch is derived from
+/// a commitment to the whole proof. A prover that knows the batch before committing can make any
+/// claimed sum verify.
+pub struct BatchChallengeSource<F: Field> {
+    challenges: Vec<F>,
+    next: usize,
+}
+
+impl<F: Field> BatchChallengeSource<F> {
+    /// Create a source producing `challenges` in order.
+    pub fn new(challenges: Vec<F>) -> Self {
+        Self {
+            challenges,
+            next: 0,
+        }
+    }
+
+    /// number of challenges that have not been produced yet
+    pub fn remaining(&self) -> usize {
+        self.challenges.len() - self.next
+    }
+}
+
+impl<F: Field> ChallengeSource<F> for BatchChallengeSource<F> {
+    /// Panics if all challenges of the batch have been produced.
+    fn next(&mut self) -> F {
+        let challenge = *self
+            .challenges
+            .get(self.next)
+            .expect("the batch of challenges is exhausted");
+        self.next += 1;
+        challenge
+    }
+}
+
+/// Challenges pulled from an external randomness beacon instead of Fiat-Shamir.
+///
+/// Every challenge is the next output of `beacon`. This is meant for running the interactive
+/// protocol with real verifier randomness: give the prover and the verifier beacons producing the
+/// same sequence. As for any `ChallengeSource`, the protocol is only sound if the prover cannot
+/// predict the output of a round before sending its message.
+pub struct BeaconChallengeSource<F: Field, B: FnMut() -> F> {
+    beacon: B,
+}
+
+impl<F: Field, B: FnMut() -> F> BeaconChallengeSource<F, B> {
+    /// Create a source that pulls its challenges from `beacon`.
+    pub fn new(beacon: B) -> Self {
+        Self { beacon }
+    }
+}
+
+impl<F: Field, B: FnMut() -> F> ChallengeSource<F> for BeaconChallengeSource<F, B> {
+    fn next(&mut self) -> F {
+        (self.beacon)()
+    }
+}
+
 // impl FeedableRNG for TranscriptRng {
 //     type Error = crate::Error;
 
@@ -102,10 +454,15 @@ mod tests {
     use ark_std::rand::Rng;
     use ark_std::rand::RngCore;
 
-    use crate::rng::{Blake2s512Rng, FeedableRNG};
+    use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+    use crate::ml_sumcheck::MLSumcheck;
+    use crate::rng::{BeaconChallengeSource, Blake2s512Rng, DynFeedableRNG, FeedableRNG, SetupRNG};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_serialize::CanonicalSerialize;
-    use ark_std::test_rng;
+    use ark_std::boxed::Box;
+    use ark_std::rc::Rc;
     use ark_std::vec::Vec;
+    use ark_std::{test_rng, UniformRand};
     use ark_test_curves::bls12_381::Fr;
 
     /// Special type of input used for test.
@@ -129,7 +486,7 @@ mod tests {
     fn test_deterministic_pseudorandom_generator<G, F>(num_iterations: u32)
     where
         F: Field,
-        G: FeedableRNG,
+        G: SetupRNG,
     {
         let mut rng = test_rng();
         for _ in 0..num_iterations {
@@ -189,4 +546,158 @@ mod tests {
     fn test_blake2s_hashing() {
         test_deterministic_pseudorandom_generator::<Blake2s512Rng, Fr>(5)
     }
+
+    #[test]
+    fn test_feed_batch_matches_sequential_feeds() {
+        let mut rng = test_rng();
+        let msgs: Vec<_> = (0..3).map(|_| TestMessage::rand(&mut rng, 100)).collect();
+        let mut sequential = Blake2s512Rng::setup();
+        for msg in &msgs {
+            sequential.feed(msg).unwrap();
+        }
+        let mut batched = Blake2s512Rng::setup();
+        batched.feed_batch(&[&msgs[0], &msgs[1], &msgs[2]]).unwrap();
+        assert_eq!(Fr::rand(&mut sequential), Fr::rand(&mut batched));
+    }
+
+    #[test]
+    fn test_beacon_challenge_source() {
+        let mut rng = test_rng();
+        let nv = 6;
+        let product: Vec<_> = (0..3)
+            .map(|_| Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let mut poly = ListOfProductsOfPolynomials::new(nv);
+        poly.add_product(product, Fr::rand(&mut rng));
+
+        let challenges: Vec<Fr> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+        let beacon = |sequence: Vec<Fr>| {
+            let mut sequence = sequence.into_iter();
+            move || sequence.next().expect("beacon is exhausted")
+        };
+
+        let mut prover_challenges = BeaconChallengeSource::new(beacon(challenges.clone()));
+        let (proof, prover_state) =
+            MLSumcheck::prove_with_challenges(&poly, &mut prover_challenges)
+                .expect("fail to prove");
+        assert_eq!(prover_state.challenges(), &challenges[..]);
+
+        let mut verifier_challenges = BeaconChallengeSource::new(beacon(challenges.clone()));
+        let subclaim = MLSumcheck::verify_with_challenges(
+            &poly.info(),
+            MLSumcheck::extract_sum(&proof),
+            &proof,
+            &mut verifier_challenges,
+        )
+        .expect("fail to verify");
+        assert_eq!(subclaim.point, challenges);
+        assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+    }
+
+    #[test]
+    fn test_dyn_feedable_rng() {
+        let mut rng = test_rng();
+        let nv = 5;
+        let product: Vec<_> = (0..3)
+            .map(|_| Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let mut poly = ListOfProductsOfPolynomials::new(nv);
+        poly.add_product(product, Fr::rand(&mut rng));
+
+        let mut boxed: Box<dyn DynFeedableRNG<Error = crate::Error>> =
+            Box::new(Blake2s512Rng::setup());
+        let mut prover_rng: &mut dyn DynFeedableRNG<Error = crate::Error> = &mut *boxed;
+        let (proof, _) =
+            MLSumcheck::prove_as_subprotocol(&mut prover_rng, &poly).expect("fail to prove");
+        // same proof as with the generator itself
+        let mut expected_rng = Blake2s512Rng::setup();
+        let (expected, _) =
+            MLSumcheck::prove_as_subprotocol(&mut expected_rng, &poly).expect("fail to prove");
+        assert_eq!(
+            MLSumcheck::proof_to_plain_bytes(&proof),
+            MLSumcheck::proof_to_plain_bytes(&expected)
+        );
+        assert_eq!(boxed.next_u64(), expected_rng.next_u64());
+
+        let subclaim = MLSumcheck::verify(&poly.info(), MLSumcheck::extract_sum(&proof), &proof)
+            .expect("fail to verify");
+        assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+    }
+
+    #[cfg(feature = "sponge")]
+    mod sponge {
+        use super::{test_deterministic_pseudorandom_generator, TestMessage};
+        use crate::ml_sumcheck::data_structures::ListOfProductsOfPolynomials;
+        use crate::ml_sumcheck::MLSumcheck;
+        use crate::rng::{FeedableRNG, SetupRNG, SpongeConfig, SpongeRng};
+        use ark_crypto_primitives::sponge::poseidon::{
+            find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+        };
+        use ark_crypto_primitives::sponge::CryptographicSponge;
+        use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+        use ark_std::rc::Rc;
+        use ark_std::{test_rng, UniformRand};
+        use ark_test_curves::bls12_381::Fr;
+
+        struct TestPoseidonConfig;
+
+        impl SpongeConfig<PoseidonSponge<Fr>> for TestPoseidonConfig {
+            fn config() -> PoseidonConfig<Fr> {
+                let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(255, 2, 8, 31, 0);
+                PoseidonConfig::new(8, 31, 17, mds, ark, 2, 1)
+            }
+        }
+
+        type PoseidonRng = SpongeRng<PoseidonSponge<Fr>, TestPoseidonConfig>;
+
+        #[test]
+        fn test_poseidon_sponge() {
+            test_deterministic_pseudorandom_generator::<PoseidonRng, Fr>(1)
+        }
+
+        #[test]
+        fn test_poseidon_sponge_prove_verify() {
+            let mut rng = test_rng();
+            let nv = 5;
+            let product: Vec<_> = (0..3)
+                .map(|_| Rc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+                .collect();
+            let mut poly = ListOfProductsOfPolynomials::new(nv);
+            poly.add_product(product, Fr::rand(&mut rng));
+
+            let mut prover_rng = PoseidonRng::setup();
+            let (proof, _) =
+                MLSumcheck::prove_as_subprotocol(&mut prover_rng, &poly).expect("fail to prove");
+            let asserted_sum = MLSumcheck::extract_sum(&proof);
+            let mut verifier_rng = PoseidonRng::setup();
+            let subclaim = MLSumcheck::verify_as_subprotocol(
+                &mut verifier_rng,
+                &poly.info(),
+                asserted_sum,
+                &proof,
+            )
+            .expect("fail to verify");
+            assert!(poly.evaluate(&subclaim.point) == subclaim.expected_evaluation);
+        }
+
+        #[test]
+        fn test_sponge_absorb_with_label() {
+            let mut rng = test_rng();
+            let msg = TestMessage::rand(&mut rng, 100);
+            let labeled = || {
+                let mut sponge_rng = PoseidonRng::setup();
+                sponge_rng.absorb_with_label(b"sumcheck", &msg).unwrap();
+                sponge_rng
+            };
+            let mut unlabeled = PoseidonRng::setup();
+            unlabeled.feed(&msg).unwrap();
+            assert_ne!(Fr::rand(&mut labeled()), Fr::rand(&mut unlabeled));
+
+            // the label is absorbed as arkworks forks a sponge
+            let forked = PoseidonSponge::new(&TestPoseidonConfig::config()).fork(b"sumcheck");
+            let mut forked = PoseidonRng::from_sponge(forked);
+            forked.feed(&msg).unwrap();
+            assert_eq!(Fr::rand(&mut labeled()), Fr::rand(&mut forked));
+        }
+    }
 }
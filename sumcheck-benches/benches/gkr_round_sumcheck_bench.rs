@@ -4,7 +4,7 @@ extern crate criterion;
 use ark_ff::Field;
 use ark_linear_sumcheck::{
     gkr_round_sumcheck::GKRRoundSumcheck,
-    rng::{Blake2s512Rng, FeedableRNG},
+    rng::{Blake2s512Rng, SetupRNG},
 };
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension};
 use ark_std::ops::Range;
@@ -2,11 +2,17 @@
 extern crate criterion;
 
 use ark_ff::Field;
-use ark_linear_sumcheck::ml_sumcheck::protocol::ListOfProductsOfPolynomials;
+use ark_linear_sumcheck::ml_sumcheck::protocol::prover::{ProverMsg, ProverWorkspace};
+use ark_linear_sumcheck::ml_sumcheck::protocol::verifier::{Interpolation, VerifyOptions};
+use ark_linear_sumcheck::ml_sumcheck::protocol::{IPForMLSumcheck, PolynomialInfo};
+use ark_linear_sumcheck::ml_sumcheck::test_utils::random_instance;
 use ark_linear_sumcheck::ml_sumcheck::MLSumcheck;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_linear_sumcheck::rng::{Blake2s512Rng, FeedableRNG, SetupRNG};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::ops::Range;
-use ark_std::rc::Rc;
+use ark_std::rand::RngCore;
 use ark_std::test_rng;
 use criterion::{black_box, BenchmarkId, Criterion};
 
@@ -18,17 +24,7 @@ fn prove_bench<F: Field>(c: &mut Criterion) {
     let mut group = c.benchmark_group("Prove");
     for nv in NUM_VARIABLES_RANGE {
         group.bench_with_input(BenchmarkId::new("ML", nv), &nv, |b, &nv| {
-            let product_1: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
-                .collect();
-            let product_2: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
-                .collect();
-            let coefficient_1 = F::rand(&mut rng);
-            let coefficient_2 = F::rand(&mut rng);
-            let mut products = ListOfProductsOfPolynomials::new(nv);
-            products.add_product(product_1, coefficient_1);
-            products.add_product(product_2, coefficient_2);
+            let (products, _) = random_instance::<F, _>(nv, (3, 4), 2, &mut rng);
             b.iter(|| MLSumcheck::prove(black_box(&products)));
         });
     }
@@ -40,20 +36,8 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
     let mut group = c.benchmark_group("Verify");
     for nv in NUM_VARIABLES_RANGE {
         group.bench_with_input(BenchmarkId::new("ML", nv), &nv, |b, &nv| {
-            let product_1: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
-                .collect();
-            let product_2: Vec<_> = (0..3)
-                .map(|_| Rc::new(DenseMultilinearExtension::<F>::rand(nv, &mut rng)))
-                .collect();
-            let coefficient_1 = F::rand(&mut rng);
-            let coefficient_2 = F::rand(&mut rng);
-            // calculate expected sum
-            let mut products = ListOfProductsOfPolynomials::new(nv);
-            products.add_product(product_1, coefficient_1);
-            products.add_product(product_2, coefficient_2);
+            let (products, expected_sum) = random_instance::<F, _>(nv, (3, 4), 2, &mut rng);
             let proof = MLSumcheck::prove(&products).unwrap();
-            let expected_sum = MLSumcheck::extract_sum(&proof);
             b.iter(|| {
                 MLSumcheck::verify(&products.info(), black_box(expected_sum), &proof).unwrap()
             });
@@ -61,9 +45,332 @@ fn verify_bench<F: Field>(c: &mut Criterion) {
     }
 }
 
+#[cfg(feature = "parallel")]
+fn batch_verify_bench<F: Field>(c: &mut Criterion) {
+    const NUM_PROOFS: usize = 1000;
+    const NUM_VARIABLES: usize = 4;
+    let mut rng = test_rng();
+
+    let instances: Vec<_> = (0..NUM_PROOFS)
+        .map(|_| {
+            let (products, expected_sum) =
+                random_instance::<F, _>(NUM_VARIABLES, (3, 4), 1, &mut rng);
+            let proof = MLSumcheck::prove(&products).unwrap();
+            (products.info(), expected_sum, proof)
+        })
+        .collect();
+    let jobs: Vec<_> = instances
+        .iter()
+        .map(|(info, expected_sum, proof)| (info.clone(), *expected_sum, proof))
+        .collect();
+
+    let mut group = c.benchmark_group("BatchVerify");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            for (info, expected_sum, proof) in &jobs {
+                MLSumcheck::verify(info, black_box(*expected_sum), proof).unwrap();
+            }
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| MLSumcheck::batch_verify_parallel(black_box(&jobs)))
+    });
+}
+
+#[cfg(feature = "parallel")]
+fn prove_many_bench<F: Field>(c: &mut Criterion) {
+    const NUM_INSTANCES: usize = 1000;
+    const NUM_VARIABLES: usize = 8;
+    let mut rng = test_rng();
+
+    let instances: Vec<_> = (0..NUM_INSTANCES)
+        .map(|_| random_instance::<F, _>(NUM_VARIABLES, (3, 4), 1, &mut rng).0)
+        .collect();
+    let pool = rayon::ThreadPoolBuilder::new().build().unwrap();
+
+    let mut group = c.benchmark_group("ProveMany");
+    group.sample_size(10);
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            for instance in &instances {
+                black_box(MLSumcheck::prove(instance).unwrap());
+            }
+        })
+    });
+    group.bench_function("thread pool", |b| {
+        b.iter(|| MLSumcheck::prove_many(black_box(&instances), &pool))
+    });
+}
+
+fn prove_round_buffer_bench<F: Field>(c: &mut Criterion) {
+    const NUM_INSTANCES: usize = 10000;
+    const NUM_VARIABLES: usize = 3;
+    let mut rng = test_rng();
+
+    let instances: Vec<_> = (0..NUM_INSTANCES)
+        .map(|_| random_instance::<F, _>(NUM_VARIABLES, (3, 4), 1, &mut rng).0)
+        .collect();
+    let challenges: Vec<_> = (0..NUM_VARIABLES)
+        .map(|_| IPForMLSumcheck::<F>::sample_round(&mut rng))
+        .collect();
+
+    let mut group = c.benchmark_group("ProveRounds");
+    group.bench_function("allocating", |b| {
+        b.iter(|| {
+            for products in &instances {
+                let mut prover_state = IPForMLSumcheck::prover_init(products);
+                let mut verifier_msg = None;
+                for challenge in &challenges {
+                    black_box(IPForMLSumcheck::prove_round(
+                        &mut prover_state,
+                        &verifier_msg,
+                    ));
+                    verifier_msg = Some(challenge.clone());
+                }
+            }
+        })
+    });
+    group.bench_function("buffer reuse", |b| {
+        b.iter(|| {
+            let mut buf = Vec::new();
+            for products in &instances {
+                let mut prover_state = IPForMLSumcheck::prover_init(products);
+                let mut verifier_msg = None;
+                for challenge in &challenges {
+                    IPForMLSumcheck::prove_round_into(&mut prover_state, &verifier_msg, &mut buf);
+                    black_box(&buf);
+                    verifier_msg = Some(challenge.clone());
+                }
+            }
+        })
+    });
+}
+
+/// Returns a proof accepted for `polynomial_info` and its claimed sum, without proving any polynomial.
+///
+/// Each round polynomial is random, with the constant term adjusted to be consistent with the
+/// previous round, so that verification runs through all rounds.
+fn consistent_proof<F: Field>(
+    polynomial_info: &PolynomialInfo,
+    rng: &mut impl RngCore,
+) -> (Vec<ProverMsg<F>>, F) {
+    let mut fs_rng = Blake2s512Rng::setup();
+    fs_rng.feed(polynomial_info).unwrap();
+    let mut proof = Vec::with_capacity(polynomial_info.num_variables);
+    let mut claimed_sum = None;
+    let mut expected = F::zero();
+    for _ in 0..polynomial_info.num_variables {
+        let mut round_polynomial =
+            DensePolynomial::<F>::rand(polynomial_info.max_multiplicands, rng);
+        let sum = round_polynomial.evaluate(&F::zero()) + round_polynomial.evaluate(&F::one());
+        match claimed_sum {
+            None => claimed_sum = Some(sum),
+            Some(_) => round_polynomial.coeffs[0] += (expected - sum) / F::from(2u64),
+        }
+        let evaluations: Vec<F> = (0..=polynomial_info.max_multiplicands as u64)
+            .map(|i| round_polynomial.evaluate(&F::from(i)))
+            .collect();
+        let mut bytes = Vec::new();
+        evaluations.serialize_uncompressed(&mut bytes).unwrap();
+        let msg = ProverMsg::deserialize_uncompressed(&bytes[..]).unwrap();
+        fs_rng.feed(&msg).unwrap();
+        expected = round_polynomial.evaluate(&F::rand(&mut fs_rng));
+        proof.push(msg);
+    }
+    (proof, claimed_sum.unwrap())
+}
+
+fn interpolation_bench<F: Field>(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let polynomial_info = PolynomialInfo {
+        max_multiplicands: 6,
+        num_variables: 24,
+    };
+    let (proof, claimed_sum) = consistent_proof::<F>(&polynomial_info, &mut rng);
+
+    let mut group = c.benchmark_group("Verify degree 6, 24 rounds");
+    for (name, interpolation) in [
+        ("Lagrange", Interpolation::Lagrange),
+        ("Newton", Interpolation::Newton),
+    ] {
+        let options = VerifyOptions {
+            interpolation,
+            ..VerifyOptions::default()
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                MLSumcheck::verify_with_options(
+                    &polynomial_info,
+                    claimed_sum,
+                    black_box(&proof),
+                    &options,
+                )
+                .unwrap()
+            })
+        });
+    }
+}
+
+fn many_products_bench<F: Field>(c: &mut Criterion) {
+    const NUM_PRODUCTS: usize = 20;
+    let mut rng = test_rng();
+
+    let mut group = c.benchmark_group("Prove 20 products");
+    for nv in 10..17 {
+        let (products, _) = random_instance::<F, _>(nv, (3, 4), NUM_PRODUCTS, &mut rng);
+        group.bench_with_input(BenchmarkId::new("ML", nv), &products, |b, products| {
+            b.iter(|| MLSumcheck::prove(products).unwrap());
+        });
+    }
+}
+
+fn workspace_bench<F: Field>(c: &mut Criterion) {
+    const NUM_INSTANCES: usize = 1000;
+    const NUM_VARIABLES: usize = 8;
+    let mut rng = test_rng();
+
+    let instances: Vec<_> = (0..NUM_INSTANCES)
+        .map(|_| random_instance::<F, _>(NUM_VARIABLES, (3, 4), 1, &mut rng).0)
+        .collect();
+
+    let mut group = c.benchmark_group("Prove many instances");
+    group.sample_size(10);
+    group.bench_function("prove", |b| {
+        b.iter(|| {
+            for instance in &instances {
+                black_box(MLSumcheck::prove(instance).unwrap());
+            }
+        })
+    });
+    group.bench_function("prove_in_workspace", |b| {
+        let mut workspace = ProverWorkspace::new();
+        b.iter(|| {
+            for instance in &instances {
+                let mut fs_rng = Blake2s512Rng::setup();
+                black_box(
+                    MLSumcheck::prove_in_workspace(&mut workspace, instance, &mut fs_rng).unwrap(),
+                );
+            }
+        })
+    });
+}
+
+/// Field elements absorbed by a Poseidon transcript when the polynomial info and the first prover
+/// message are fed one at a time, or together with `FeedableRNG::feed_batch`.
+///
+/// The sponge packs each fed message into whole field elements, and permutes once per `rate`
+/// elements absorbed in a row, so batching saves the padding of every message but the last. The
+/// counts are printed before the feeds are timed.
+#[cfg(feature = "sponge")]
+mod sponge {
+    use super::*;
+    use ark_crypto_primitives::sponge::poseidon::{
+        find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+    };
+    use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+    use ark_linear_sumcheck::rng::{SpongeConfig, SpongeRng};
+    use ark_test_curves::bls12_381::Fr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const RATE: usize = 2;
+
+    /// number of field elements absorbed by all `CountingSponge`s
+    static ABSORBED_ELEMENTS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Poseidon sponge counting the field elements it absorbs.
+    #[derive(Clone)]
+    struct CountingSponge(PoseidonSponge<Fr>);
+
+    impl CryptographicSponge for CountingSponge {
+        type Config = PoseidonConfig<Fr>;
+
+        fn new(config: &Self::Config) -> Self {
+            Self(PoseidonSponge::new(config))
+        }
+
+        fn absorb(&mut self, input: &impl Absorb) {
+            let len = input.to_sponge_field_elements_as_vec::<Fr>().len();
+            ABSORBED_ELEMENTS.fetch_add(len, Ordering::Relaxed);
+            self.0.absorb(input)
+        }
+
+        fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+            self.0.squeeze_bytes(num_bytes)
+        }
+
+        fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+            self.0.squeeze_bits(num_bits)
+        }
+    }
+
+    struct BenchPoseidonConfig;
+
+    impl SpongeConfig<CountingSponge> for BenchPoseidonConfig {
+        fn config() -> PoseidonConfig<Fr> {
+            let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(255, RATE, 8, 31, 0);
+            PoseidonConfig::new(8, 31, 17, mds, ark, RATE, 1)
+        }
+    }
+
+    type CountingPoseidonRng = SpongeRng<CountingSponge, BenchPoseidonConfig>;
+
+    fn absorbed_elements(feed: impl Fn(&mut CountingPoseidonRng)) -> usize {
+        let mut fs_rng = CountingPoseidonRng::setup();
+        ABSORBED_ELEMENTS.store(0, Ordering::Relaxed);
+        feed(&mut fs_rng);
+        ABSORBED_ELEMENTS.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn feed_batch_bench(c: &mut Criterion) {
+        const NUM_VARIABLES: usize = 12;
+        let mut rng = test_rng();
+        let (polynomial, _) = random_instance::<Fr, _>(NUM_VARIABLES, (3, 4), 1, &mut rng);
+        let info = polynomial.info();
+        let mut prover_state = IPForMLSumcheck::prover_init(&polynomial);
+        let first = IPForMLSumcheck::prove_round(&mut prover_state, &None);
+
+        let sequential = |fs_rng: &mut CountingPoseidonRng| {
+            fs_rng.feed(&info).unwrap();
+            fs_rng.feed(&first).unwrap();
+        };
+        let batched = |fs_rng: &mut CountingPoseidonRng| {
+            fs_rng.feed_batch(&[&info, &first]).unwrap();
+        };
+        for (name, elements) in [
+            ("sequential feeds", absorbed_elements(sequential)),
+            ("feed_batch", absorbed_elements(batched)),
+        ] {
+            println!(
+                "{}: {} field elements absorbed, {} permutations",
+                name,
+                elements,
+                (elements + RATE - 1) / RATE
+            );
+        }
+
+        let mut group = c.benchmark_group("Poseidon feeds of the info and first message");
+        group.bench_function("sequential feeds", |b| {
+            b.iter(|| sequential(&mut CountingPoseidonRng::setup()))
+        });
+        group.bench_function("feed_batch", |b| {
+            b.iter(|| batched(&mut CountingPoseidonRng::setup()))
+        });
+    }
+}
+
 fn bench_bls_381(c: &mut Criterion) {
     prove_bench::<ark_test_curves::bls12_381::Fr>(c);
     verify_bench::<ark_test_curves::bls12_381::Fr>(c);
+    prove_round_buffer_bench::<ark_test_curves::bls12_381::Fr>(c);
+    workspace_bench::<ark_test_curves::bls12_381::Fr>(c);
+    many_products_bench::<ark_test_curves::bls12_381::Fr>(c);
+    interpolation_bench::<ark_test_curves::bls12_381::Fr>(c);
+    #[cfg(feature = "parallel")]
+    batch_verify_bench::<ark_test_curves::bls12_381::Fr>(c);
+    #[cfg(feature = "parallel")]
+    prove_many_bench::<ark_test_curves::bls12_381::Fr>(c);
+    #[cfg(feature = "sponge")]
+    sponge::feed_batch_bench(c);
 }
 
 criterion_group!(benches, bench_bls_381);